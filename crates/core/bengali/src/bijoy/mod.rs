@@ -0,0 +1,439 @@
+//! Conversion between Bijoy ANSI text and Unicode
+//!
+//! Bijoy text is written for SutonnyMJ-family fonts, which draw Bengali
+//! glyphs in place of Latin-1 characters. The text is expected as those
+//! characters (Windows-1252), the way it appears in a font without Bengali
+//! glyphs, e.g. `Avgvi †mvbvi evsjv` for আমার সোনার বাংলা.
+//!
+//! Bijoy stores glyphs in visual order: ি, ে and ৈ come before the consonant
+//! cluster they follow in Unicode, ো and ৌ are split around it, and the reph
+//! comes after the cluster it sits on.
+
+use crate::utils::{is_consonant, HASANTA, ZWJ};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Bijoy glyphs and the Unicode text they stand for.
+///
+/// Where several glyphs stand for the same text, the first one is used when
+/// converting to Bijoy.
+#[rustfmt::skip]
+const GLYPHS: &[(&str, &str)] = &[
+    // Independent vowels and signs
+    ("Av", "আ"), ("A", "অ"), ("B", "ই"), ("C", "ঈ"), ("D", "উ"), ("E", "ঊ"),
+    ("F", "ঋ"), ("G", "এ"), ("H", "ঐ"), ("I", "ও"), ("J", "ঔ"),
+    ("s", "ং"), ("t", "ঃ"), ("u", "ঁ"),
+    // Consonants
+    ("K", "ক"), ("L", "খ"), ("M", "গ"), ("N", "ঘ"), ("O", "ঙ"), ("P", "চ"), ("Q", "ছ"),
+    ("R", "জ"), ("S", "ঝ"), ("T", "ঞ"), ("U", "ট"), ("V", "ঠ"), ("W", "ড"), ("X", "ঢ"),
+    ("Y", "ণ"), ("Z", "ত"), ("_", "থ"), ("`", "দ"), ("a", "ধ"), ("b", "ন"), ("c", "প"),
+    ("d", "ফ"), ("e", "ব"), ("f", "ভ"), ("g", "ম"), ("h", "য"), ("i", "র"), ("j", "ল"),
+    ("k", "শ"), ("l", "ষ"), ("m", "স"), ("n", "হ"), ("o", "\u{09DC}"), ("p", "\u{09DD}"),
+    ("q", "\u{09DF}"), ("r", "ৎ"),
+    // Vowel signs
+    ("v", "া"), ("w", "ি"), ("x", "ী"), ("y", "ু"), ("z", "ু"), ("“", "ু"), ("~", "ূ"),
+    ("ƒ", "ূ"), ("…", "ৃ"), ("„", "ৃ"), ("‡", "ে"), ("†", "ে"), ("‰", "ৈ"), ("ˆ", "ৈ"),
+    ("Š", "ৗ"),
+    // Hasanta, reph and phalas
+    ("&", "্"), ("©", "র্"), ("i¨", "র\u{200D}্য"), ("¨", "্য"), ("ª", "্র"), ("«", "্র"),
+    ("¡", "্ব"), ("^", "্ব"), ("¦", "্ব"), ("Ÿ", "্ব"), ("¥", "্ম"), ("§", "্ম"),
+    ("ø", "্ল"), ("¬", "্ল"), ("œ", "্ন"),
+    // Conjuncts
+    ("°", "ক্ক"), ("±", "ক্ট"), ("³", "ক্ত"), ("µ", "ক্র"), ("¶", "ক্ষ"), ("·", "ক্স"),
+    ("K¡", "ক্ব"), ("Kø", "ক্ল"), ("»", "গ্ধ"), ("Mœ", "গ্ন"), ("M¥", "গ্ম"), ("Mø", "গ্ল"),
+    ("¼", "ঙ্ক"), ("½", "ঙ্গ"), ("•L", "ঙ্খ"), ("•N", "ঙ্ঘ"),
+    ("”P", "চ্চ"), ("”Q", "চ্ছ"), ("”T", "চ্ঞ"),
+    ("¾", "জ্জ"), ("À", "জ্ঝ"), ("Á", "জ্ঞ"), ("R¡", "জ্ব"),
+    ("Â", "ঞ্চ"), ("Ã", "ঞ্ছ"), ("Ä", "ঞ্জ"), ("Å", "ঞ্ঝ"),
+    ("Æ", "ট্ট"), ("U¡", "ট্ব"), ("U¥", "ট্ম"), ("Ç", "ড্ড"),
+    ("È", "ণ্ট"), ("É", "ণ্ঠ"), ("Ê", "ণ্ড"),
+    ("Ë", "ত্ত"), ("Ì", "ত্থ"), ("Z¥", "ত্ম"), ("Z¡", "ত্ব"), ("Î", "ত্র"), ("_¡", "থ্ব"),
+    ("˜M", "দ্গ"), ("˜N", "দ্ঘ"), ("Ï", "দ্দ"), ("×", "দ্ধ"), ("Ø", "দ্ব"), ("™¢", "দ্ভ"),
+    ("Ù", "দ্ম"), ("a¥", "ধ্ম"), ("aŸ", "ধ্ব"),
+    ("›U", "ন্ট"), ("›V", "ন্ঠ"), ("š—", "ন্ত"), ("š‘", "ন্তু"), ("š¿", "ন্ত্র"), ("š’", "ন্থ"),
+    ("›`", "ন্দ"), ("Ü", "ন্ধ"), ("bœ", "ন্ন"), ("š^", "ন্ব"), ("b¥", "ন্ম"), ("Ý", "ন্স"),
+    ("Þ", "প্ট"), ("ß", "প্ত"), ("cœ", "প্ন"), ("à", "প্প"), ("cø", "প্ল"), ("á", "প্স"),
+    ("d¬", "ফ্ল"), ("â", "ব্জ"), ("ã", "ব্দ"), ("ä", "ব্ধ"), ("eŸ", "ব্ব"), ("eø", "ব্ল"),
+    ("å", "ভ্র"), ("gœ", "ম্ন"), ("¤ú", "ম্প"), ("ç", "ম্ফ"), ("¤^", "ম্ব"), ("¤¢", "ম্ভ"),
+    ("¤§", "ম্ম"), ("¤ø", "ম্ল"),
+    ("é", "ল্ক"), ("ê", "ল্গ"), ("ë", "ল্ট"), ("ì", "ল্ড"), ("í", "ল্প"), ("î", "ল্ফ"),
+    ("j¦", "ল্ব"), ("j¥", "ল্ম"), ("jø", "ল্ল"),
+    ("ð", "শ্চ"), ("kœ", "শ্ন"), ("k¦", "শ্ব"), ("k¥", "শ্ম"), ("kø", "শ্ল"),
+    ("®‹", "ষ্ক"), ("®Œ", "ষ্ক্র"), ("ó", "ষ্ট"), ("ô", "ষ্ঠ"), ("ò", "ষ্ণ"), ("®ú", "ষ্প"),
+    ("õ", "ষ্ফ"), ("®§", "ষ্ম"),
+    ("¯‹", "স্ক"), ("¯Œ", "স্ক্র"), ("ö", "স্খ"), ("÷", "স্ট"), ("¯Í", "স্ত"), ("¯‘", "স্তু"),
+    ("¯¿", "স্ত্র"), ("¯’", "স্থ"), ("mœ", "স্ন"), ("¯ú", "স্প"), ("ù", "স্ফ"), ("¯^", "স্ব"),
+    ("¯§", "স্ম"), ("¯ø", "স্ল"),
+    ("ý", "হ্ন"), ("nè", "হ্ণ"), ("nŸ", "হ্ব"), ("þ", "হ্ম"), ("n¬", "হ্ল"),
+    // Consonants joined with a vowel sign
+    ("¸", "গু"), ("i“", "রু"), ("iƒ", "রূ"), ("ï", "শু"), ("û", "হু"), ("ü", "হৃ"),
+    // Digits and punctuation
+    ("0", "০"), ("1", "১"), ("2", "২"), ("3", "৩"), ("4", "৪"), ("5", "৫"), ("6", "৬"),
+    ("7", "৭"), ("8", "৮"), ("9", "৯"), ("|", "।"), ("$", "৳"),
+    ("Ô", "‘"), ("Õ", "’"), ("Ò", "“"), ("Ó", "”"),
+];
+
+/// Glyphs that only appear as part of a conjunct, e.g. the স্ of ¯Í
+#[rustfmt::skip]
+const HALF_FORMS: &[(&str, &str)] = &[
+    ("¤", "ম্"), ("®", "ষ্"), ("¯", "স্"), ("š", "ন্"), ("›", "ন্"), ("”", "চ্"),
+    ("˜", "দ্"), ("™", "দ্"), ("•", "ঙ্"),
+    ("‹", "্ক"), ("Œ", "্ক্র"), ("—", "্ত"), ("Í", "্ত"), ("‘", "্তু"), ("’", "্থ"),
+    ("¿", "্ত্র"), ("¢", "্ভ"), ("£", "্ভ্র"), ("ú", "্প"), ("è", "্ণ"),
+];
+
+/// Unicode spellings written the same way as one listed in [`GLYPHS`]
+#[rustfmt::skip]
+const UNICODE_VARIANTS: &[(&str, &str)] = &[
+    ("&", "্\u{200C}"), ("‡v", "ো"), ("‡Š", "ৌ"),
+];
+
+/// Converts text between Bijoy ANSI and Unicode
+#[derive(Debug, Clone)]
+pub struct BijoyConverter {
+    to_unicode: BTreeMap<&'static str, &'static str>,
+    to_bijoy: BTreeMap<&'static str, &'static str>,
+    /// Longest key of either map, in characters
+    longest: usize,
+}
+
+impl BijoyConverter {
+    pub fn new() -> Self {
+        let mut to_unicode = BTreeMap::new();
+        let mut to_bijoy = BTreeMap::new();
+        for &(bijoy, unicode) in GLYPHS {
+            to_unicode.entry(bijoy).or_insert(unicode);
+            to_bijoy.entry(unicode).or_insert(bijoy);
+        }
+        for &(bijoy, unicode) in HALF_FORMS {
+            to_unicode.entry(bijoy).or_insert(unicode);
+        }
+        for &(bijoy, unicode) in UNICODE_VARIANTS {
+            to_bijoy.entry(unicode).or_insert(bijoy);
+        }
+
+        let longest = to_unicode
+            .keys()
+            .chain(to_bijoy.keys())
+            .map(|key| key.chars().count())
+            .max()
+            .unwrap_or(1);
+
+        Self {
+            to_unicode,
+            to_bijoy,
+            longest,
+        }
+    }
+
+    /// Converts Bijoy ANSI text to Unicode in logical order.
+    ///
+    /// Characters with no Bijoy glyph are kept as they are.
+    pub fn to_unicode(&self, text: &str) -> String {
+        let mut visual: Vec<Glyph> = Vec::new();
+        let mut rest = text;
+
+        while !rest.is_empty() {
+            let (len, unicode) = longest_match(&self.to_unicode, rest, self.longest);
+            rest = &rest[len..];
+
+            if unicode == "র্" {
+                visual.push(Glyph::Reph);
+                continue;
+            }
+            for ch in unicode.chars() {
+                // A half form followed by a joined form, e.g. স্ and ্ত
+                if ch == HASANTA && visual.last() == Some(&Glyph::Char(HASANTA)) {
+                    continue;
+                }
+                visual.push(Glyph::Char(ch));
+            }
+        }
+
+        let logical = place_reph(visual);
+        place_pre_base_signs(&logical)
+    }
+
+    /// Converts Unicode text to Bijoy ANSI in visual order.
+    ///
+    /// Characters with no Bijoy glyph are kept as they are, and conjuncts
+    /// with no glyph of their own are written with a visible hasanta.
+    pub fn to_bijoy(&self, text: &str) -> String {
+        // ড়, ঢ় and য় may come as the letter followed by a nukta
+        let text = text
+            .replace("ড\u{09BC}", "\u{09DC}")
+            .replace("ঢ\u{09BC}", "\u{09DD}")
+            .replace("য\u{09BC}", "\u{09DF}");
+        let chars: Vec<char> = text.chars().collect();
+        let starts: Vec<usize> = text.char_indices().map(|(start, _)| start).collect();
+        let mut bijoy = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let reph = chars[i] == 'র'
+                && chars.get(i + 1) == Some(&HASANTA)
+                && chars.get(i + 2).is_some_and(|&ch| is_consonant(ch));
+            let start = if reph { i + 2 } else { i };
+
+            if !is_consonant(chars[start]) {
+                let rest = &text[starts[i]..];
+                let (len, glyph) = longest_match(&self.to_bijoy, rest, self.longest);
+                bijoy.push_str(glyph);
+                i += rest[..len].chars().count();
+                continue;
+            }
+
+            let end = start + cluster_len(&chars[start..]);
+            let cluster: String = chars[start..end].iter().collect();
+            let sign = chars.get(end).copied().filter(|&ch| is_vowel_sign(ch));
+            i = end + usize::from(sign.is_some());
+
+            let reph = if reph { "©" } else { "" };
+            match sign {
+                Some(sign @ ('ি' | 'ে' | 'ৈ' | 'ো' | 'ৌ')) => {
+                    bijoy.push_str(match sign {
+                        'ি' => "w",
+                        'ৈ' => "‰",
+                        _ => "‡",
+                    });
+                    bijoy.push_str(&self.encode(&cluster));
+                    bijoy.push_str(reph);
+                    bijoy.push_str(match sign {
+                        'ো' => "v",
+                        'ৌ' => "Š",
+                        _ => "",
+                    });
+                }
+                Some(sign) if !reph.is_empty() => {
+                    bijoy.push_str(&self.encode(&cluster));
+                    bijoy.push_str(reph);
+                    bijoy.push_str(&self.encode(sign.encode_utf8(&mut [0; 4])));
+                }
+                Some(sign) => {
+                    bijoy.push_str(&self.encode(&format!("{}{}", cluster, sign)));
+                }
+                None => {
+                    bijoy.push_str(&self.encode(&cluster));
+                    bijoy.push_str(reph);
+                }
+            }
+        }
+
+        bijoy
+    }
+
+    /// Writes Unicode text glyph by glyph, longest match first
+    fn encode(&self, text: &str) -> String {
+        let mut bijoy = String::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let (len, glyph) = longest_match(&self.to_bijoy, rest, self.longest);
+            bijoy.push_str(glyph);
+            rest = &rest[len..];
+        }
+        bijoy
+    }
+}
+
+impl Default for BijoyConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A glyph of Bijoy text in visual order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Glyph {
+    Char(char),
+    Reph,
+}
+
+/// Finds the longest key of `map`, at most `longest` characters, that
+/// `text` starts with, falling back to the first character as it is.
+///
+/// Returns the byte length matched and its value.
+fn longest_match<'a>(
+    map: &BTreeMap<&'static str, &'static str>,
+    text: &'a str,
+    longest: usize,
+) -> (usize, &'a str) {
+    let first = text.chars().next().map_or(0, char::len_utf8);
+    let mut found = (first, &text[..first]);
+    let ends = text.char_indices().map(|(start, ch)| start + ch.len_utf8());
+    for end in ends.take(longest) {
+        if let Some(&value) = map.get(&text[..end]) {
+            found = (end, value);
+        }
+    }
+    found
+}
+
+/// Number of characters in the consonant cluster `chars` starts with
+fn cluster_len(chars: &[char]) -> usize {
+    let mut len = 1;
+    loop {
+        let joiner = usize::from(chars.get(len) == Some(&ZWJ));
+        let hasanta = len + joiner;
+        if chars.get(hasanta) == Some(&HASANTA)
+            && chars.get(hasanta + 1).is_some_and(|&ch| is_consonant(ch))
+        {
+            len = hasanta + 2;
+        } else {
+            return len;
+        }
+    }
+}
+
+/// Moves each reph before the consonant cluster it was written after
+fn place_reph(visual: Vec<Glyph>) -> Vec<char> {
+    let mut logical: Vec<char> = Vec::with_capacity(visual.len());
+
+    for glyph in visual {
+        let ch = match glyph {
+            Glyph::Char(ch) => ch,
+            Glyph::Reph => {
+                let mut start = logical.len();
+                while start > 0 && is_vowel_sign(logical[start - 1]) {
+                    start -= 1;
+                }
+                start = cluster_start(&logical[..start]).unwrap_or(logical.len());
+                logical.splice(start..start, ['র', HASANTA]);
+                continue;
+            }
+        };
+        logical.push(ch);
+    }
+
+    logical
+}
+
+/// Start of the consonant cluster that `chars` ends with
+fn cluster_start(chars: &[char]) -> Option<usize> {
+    let mut start = chars.len().checked_sub(1)?;
+    if !is_consonant(chars[start]) {
+        return None;
+    }
+    loop {
+        let mut hasanta = start;
+        if hasanta == 0 || chars[hasanta - 1] != HASANTA {
+            return Some(start);
+        }
+        hasanta -= 1;
+        let mut consonant = hasanta;
+        if consonant > 0 && chars[consonant - 1] == ZWJ {
+            consonant -= 1;
+        }
+        if consonant == 0 || !is_consonant(chars[consonant - 1]) {
+            return Some(start);
+        }
+        start = consonant - 1;
+    }
+}
+
+/// Moves ি, ে and ৈ after the consonant cluster they were written before,
+/// joining ে with a following া or ৗ into ো or ৌ
+fn place_pre_base_signs(chars: &[char]) -> String {
+    let mut text = String::with_capacity(chars.len() * 3);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let sign = chars[i];
+        if !matches!(sign, 'ি' | 'ে' | 'ৈ') {
+            text.push(sign);
+            i += 1;
+            continue;
+        }
+
+        let rest = &chars[i + 1..];
+        let len = match rest.first() {
+            Some(&ch) if is_consonant(ch) => cluster_len(rest),
+            _ => 0,
+        };
+        text.extend(&rest[..len]);
+        i += 1 + len;
+
+        match (sign, chars.get(i)) {
+            ('ে', Some('া')) => text.push('ো'),
+            ('ে', Some('ৗ')) => text.push('ৌ'),
+            _ => {
+                text.push(sign);
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    text
+}
+
+fn is_vowel_sign(ch: char) -> bool {
+    matches!(ch, '\u{09BE}'..='\u{09CC}' | '\u{09D7}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_unicode() {
+        let converter = BijoyConverter::new();
+        let cases = [
+            ("Avgvi ‡mvbvi evsjv", "আমার সোনার বাংলা"),
+            ("Avgvi †mvbvi evsjv", "আমার সোনার বাংলা"),
+            ("wKš‘", "কিন্তু"),
+            ("e¨vKiY", "ব্যাকরণ"),
+            ("‡Kv_vq", "কোথা\u{09DF}"),
+            ("‡KŠkj", "কৌশল"),
+            ("¯‹zj", "স্কুল"),
+            ("Kg©x", "কর্মী"),
+            ("Kvh©", "কার্য"),
+            ("a‡g©", "ধর্মে"),
+            ("Dr¯Í", "উৎস্ত"),
+            ("i¨vei", "র\u{200D}্যাবর"),
+            ("1971 mv‡j|", "১৯৭১ সালে।"),
+        ];
+        for (bijoy, unicode) in cases {
+            assert_eq!(converter.to_unicode(bijoy), unicode, "{}", bijoy);
+        }
+    }
+
+    #[test]
+    fn test_to_bijoy() {
+        let converter = BijoyConverter::new();
+        assert_eq!(converter.to_bijoy("আমার সোনার বাংলা"), "Avgvi ‡mvbvi evsjv");
+        assert_eq!(converter.to_bijoy("কর্মী"), "Kg©x");
+        assert_eq!(converter.to_bijoy("ধর্মে"), "a‡g©");
+        assert_eq!(converter.to_bijoy("কৌশল"), "‡KŠkj");
+        assert_eq!(converter.to_bijoy("রুমাল"), "i“gvj");
+        assert_eq!(converter.to_bijoy("কোথা\u{09DF}"), "‡Kv_vq");
+        assert_eq!(converter.to_bijoy("কোথায\u{09BC}"), "‡Kv_vq");
+        assert_eq!(converter.to_bijoy("ক্\u{200C}ল"), "K&j");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let converter = BijoyConverter::new();
+        let words = [
+            "বাংলাদেশ",
+            "বিদ্যাল\u{09DF}",
+            "স্ত্রী",
+            "ক্ষমা",
+            "রাষ্ট্র",
+            "কর্তব্য",
+            "পূর্বে",
+            "উৎসব",
+            "দুঃখ",
+            "চাঁদ",
+            "শুক্রবার",
+            "সন্ধ্যা",
+            "গণতন্ত্র",
+            "র\u{200D}্যাব",
+        ];
+        for word in words {
+            let bijoy = converter.to_bijoy(word);
+            assert_eq!(converter.to_unicode(&bijoy), word, "{}", bijoy);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Consonant pairs that form conjuncts in written Bengali
+
+use crate::utils::is_consonant;
+
+/// Consonant pairs that Bengali words join with a hasanta.
+///
+/// Longer clusters are checked pair by pair, so ন্ত্র is valid because both
+/// ন্ত and ত্র are. Reph (র followed by any consonant) is always valid and
+/// not listed here.
+#[rustfmt::skip]
+pub const STANDARD_CONJUNCTS: &[(char, char)] = &[
+    ('ক', 'ক'), ('ক', 'ট'), ('ক', 'ত'), ('ক', 'ন'), ('ক', 'ব'), ('ক', 'ম'),
+    ('ক', 'য'), ('ক', 'র'), ('ক', 'ল'), ('ক', 'ষ'), ('ক', 'স'),
+    ('খ', 'য'), ('খ', 'র'),
+    ('গ', 'ধ'), ('গ', 'ন'), ('গ', 'ব'), ('গ', 'ম'), ('গ', 'য'), ('গ', 'র'), ('গ', 'ল'),
+    ('ঘ', 'ন'), ('ঘ', 'য'), ('ঘ', 'র'),
+    ('ঙ', 'ক'), ('ঙ', 'খ'), ('ঙ', 'গ'), ('ঙ', 'ঘ'), ('ঙ', 'ম'),
+    ('চ', 'চ'), ('চ', 'ছ'), ('চ', 'ঞ'), ('চ', 'য'), ('চ', 'র'),
+    ('ছ', 'ব'), ('ছ', 'য'), ('ছ', 'র'),
+    ('জ', 'জ'), ('জ', 'ঝ'), ('জ', 'ঞ'), ('জ', 'ব'), ('জ', 'য'), ('জ', 'র'),
+    ('ঝ', 'য'),
+    ('ঞ', 'চ'), ('ঞ', 'ছ'), ('ঞ', 'জ'), ('ঞ', 'ঝ'),
+    ('ট', 'ট'), ('ট', 'ব'), ('ট', 'ম'), ('ট', 'য'), ('ট', 'র'),
+    ('ঠ', 'য'), ('ঠ', 'র'),
+    ('ড', 'ড'), ('ড', 'ম'), ('ড', 'য'), ('ড', 'র'),
+    ('ঢ', 'য'), ('ঢ', 'র'),
+    ('ণ', 'ট'), ('ণ', 'ঠ'), ('ণ', 'ড'), ('ণ', 'ঢ'), ('ণ', 'ণ'), ('ণ', 'ব'), ('ণ', 'ম'),
+    ('ণ', 'য'),
+    ('ত', 'ত'), ('ত', 'থ'), ('ত', 'ন'), ('ত', 'ব'), ('ত', 'ম'), ('ত', 'য'), ('ত', 'র'),
+    ('থ', 'ব'), ('থ', 'য'), ('থ', 'র'),
+    ('দ', 'গ'), ('দ', 'ঘ'), ('দ', 'দ'), ('দ', 'ধ'), ('দ', 'ব'), ('দ', 'ভ'), ('দ', 'ম'),
+    ('দ', 'য'), ('দ', 'র'),
+    ('ধ', 'ন'), ('ধ', 'ব'), ('ধ', 'ম'), ('ধ', 'য'), ('ধ', 'র'),
+    ('ন', 'ট'), ('ন', 'ঠ'), ('ন', 'ড'), ('ন', 'ত'), ('ন', 'থ'), ('ন', 'দ'), ('ন', 'ধ'),
+    ('ন', 'ন'), ('ন', 'ব'), ('ন', 'ম'), ('ন', 'য'), ('ন', 'র'), ('ন', 'স'),
+    ('প', 'ট'), ('প', 'ত'), ('প', 'ন'), ('প', 'প'), ('প', 'য'), ('প', 'র'), ('প', 'ল'),
+    ('প', 'স'),
+    ('ফ', 'য'), ('ফ', 'র'), ('ফ', 'ল'),
+    ('ব', 'জ'), ('ব', 'দ'), ('ব', 'ধ'), ('ব', 'ব'), ('ব', 'য'), ('ব', 'র'), ('ব', 'ল'),
+    ('ভ', 'য'), ('ভ', 'র'),
+    ('ম', 'ন'), ('ম', 'প'), ('ম', 'ফ'), ('ম', 'ব'), ('ম', 'ভ'), ('ম', 'ম'), ('ম', 'য'),
+    ('ম', 'র'), ('ম', 'ল'),
+    ('য', 'য'),
+    ('ল', 'ক'), ('ল', 'গ'), ('ল', 'ট'), ('ল', 'ড'), ('ল', 'প'), ('ল', 'ফ'), ('ল', 'ব'),
+    ('ল', 'ম'), ('ল', 'য'), ('ল', 'ল'),
+    ('শ', 'চ'), ('শ', 'ছ'), ('শ', 'ন'), ('শ', 'ব'), ('শ', 'ম'), ('শ', 'য'), ('শ', 'র'),
+    ('শ', 'ল'),
+    ('ষ', 'ক'), ('ষ', 'ট'), ('ষ', 'ঠ'), ('ষ', 'ণ'), ('ষ', 'প'), ('ষ', 'ফ'), ('ষ', 'ব'),
+    ('ষ', 'ম'), ('ষ', 'য'),
+    ('স', 'ক'), ('স', 'খ'), ('স', 'ট'), ('স', 'ত'), ('স', 'থ'), ('স', 'ন'), ('স', 'প'),
+    ('স', 'ফ'), ('স', 'ব'), ('স', 'ম'), ('স', 'য'), ('স', 'র'), ('স', 'ল'),
+    ('হ', 'ণ'), ('হ', 'ন'), ('হ', 'ব'), ('হ', 'ম'), ('হ', 'য'), ('হ', 'র'), ('হ', 'ল'),
+];
+
+/// Checks whether `first` followed by a hasanta and `second` is a conjunct
+/// found in Bengali words
+pub fn is_standard_conjunct(first: char, second: char) -> bool {
+    if first == 'র' {
+        return is_consonant(second);
+    }
+    STANDARD_CONJUNCTS.contains(&(first, second))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_conjuncts() {
+        assert!(is_standard_conjunct('ক', 'ষ'));
+        assert!(is_standard_conjunct('ব', 'দ'));
+        assert!(is_standard_conjunct('ন', 'ত'));
+        assert!(!is_standard_conjunct('ত', 'ল'));
+        assert!(!is_standard_conjunct('খ', 'ক'));
+    }
+
+    #[test]
+    fn test_reph_joins_any_consonant() {
+        assert!(is_standard_conjunct('র', 'ক'));
+        assert!(is_standard_conjunct('র', 'য'));
+        assert!(!is_standard_conjunct('র', 'া'));
+    }
+
+    #[test]
+    fn test_table_has_only_consonants() {
+        for &(first, second) in STANDARD_CONJUNCTS {
+            assert!(is_consonant(first) && is_consonant(second));
+        }
+    }
+}
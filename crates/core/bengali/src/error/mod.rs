@@ -1,11 +1,14 @@
 //! Error types and handling
 
+use alloc::string::String;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[cfg(feature = "std")]
     #[error("System error: {0}")]
     SystemError(#[from] std::io::Error),
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
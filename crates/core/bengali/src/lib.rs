@@ -1,16 +1,26 @@
 //! bengali - Part of the Obadh Bengali Input Method
 //!
 //! This module provides core functionality for the input method engine.
+//!
+//! Without the default `std` feature the crate builds as `no_std` with
+//! `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+pub mod bijoy;
+pub mod conjuncts;
 pub mod error;
+pub mod normalize;
 pub mod types;
 pub mod utils;
+pub mod validate;
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn sanity_check() {
         assert!(true);
     }
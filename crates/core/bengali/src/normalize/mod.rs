@@ -0,0 +1,204 @@
+//! Bengali-aware Unicode normalization
+//!
+//! Unicode NFC composes ো and ৌ from their parts but splits ড়, ঢ় and য় into
+//! the letter and a nukta. Most Bengali text and fonts expect the single code
+//! points for those, so the nukta form is chosen separately.
+
+use crate::utils::HASANTA;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+pub const NUKTA: char = '\u{09BC}';
+
+/// The letters with a nukta that have a code point of their own
+const NUKTA_LETTERS: &[(char, char)] = &[
+    ('\u{09DC}', '\u{09A1}'),
+    ('\u{09DD}', '\u{09A2}'),
+    ('\u{09DF}', '\u{09AF}'),
+];
+
+/// The two-part vowel signs and their parts
+const SPLIT_VOWELS: &[(char, [char; 2])] = &[
+    ('\u{09CB}', ['\u{09C7}', '\u{09BE}']),
+    ('\u{09CC}', ['\u{09C7}', '\u{09D7}']),
+];
+
+/// How ড়, ঢ় and য় are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NuktaForm {
+    /// One code point each, e.g. U+09DF for য়
+    #[default]
+    Precomposed,
+    /// The letter followed by a nukta, as in NFC
+    Decomposed,
+}
+
+/// A normal form for Bengali text.
+///
+/// The default is [`Normalizer::precomposed`], which is not NFC: NFC keeps
+/// ড়, ঢ় and য় decomposed. [`Normalizer::nfc`] gives NFC itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Normalizer {
+    /// Compose ো and ৌ and order the nukta before the hasanta, as NFC does
+    pub canonical: bool,
+    pub nukta: NuktaForm,
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self::precomposed()
+    }
+}
+
+impl Normalizer {
+    /// Returns the normalizer that gives exactly NFC for Bengali text
+    pub fn nfc() -> Self {
+        Self {
+            canonical: true,
+            nukta: NuktaForm::Decomposed,
+        }
+    }
+
+    /// Returns the normalizer that gives NFC, except that ড়, ঢ় and য় are
+    /// written as single code points
+    pub fn precomposed() -> Self {
+        Self {
+            canonical: true,
+            nukta: NuktaForm::Precomposed,
+        }
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        let mut chars: Vec<char> = Vec::with_capacity(text.len());
+        for ch in text.chars() {
+            match decompose(ch, self.canonical) {
+                Some(parts) => chars.extend(parts),
+                None => chars.push(ch),
+            }
+        }
+
+        if self.canonical {
+            // The nukta has a lower combining class than the hasanta
+            for i in 1..chars.len() {
+                if chars[i - 1] == HASANTA && chars[i] == NUKTA {
+                    chars.swap(i - 1, i);
+                }
+            }
+        }
+
+        let mut normalized = String::with_capacity(text.len());
+        let mut previous: Option<char> = None;
+        for ch in chars {
+            let composed = previous.and_then(|first| self.compose(first, ch));
+            if let Some(composed) = composed {
+                normalized.pop();
+                normalized.push(composed);
+                previous = Some(composed);
+            } else {
+                normalized.push(ch);
+                previous = Some(ch);
+            }
+        }
+
+        normalized
+    }
+
+    /// Checks whether `text` is already in the normal form
+    pub fn is_normalized(&self, text: &str) -> bool {
+        self.normalize(text) == text
+    }
+
+    fn compose(&self, first: char, second: char) -> Option<char> {
+        if self.canonical {
+            if let Some(&(vowel, _)) = SPLIT_VOWELS
+                .iter()
+                .find(|(_, parts)| *parts == [first, second])
+            {
+                return Some(vowel);
+            }
+        }
+        if self.nukta == NuktaForm::Precomposed && second == NUKTA {
+            return NUKTA_LETTERS
+                .iter()
+                .find(|&&(_, letter)| letter == first)
+                .map(|&(composed, _)| composed);
+        }
+        None
+    }
+}
+
+/// Splits a letter with a nukta, and a two-part vowel sign when `vowels` is
+/// set
+fn decompose(ch: char, vowels: bool) -> Option<[char; 2]> {
+    if let Some(&(_, letter)) = NUKTA_LETTERS.iter().find(|&&(composed, _)| composed == ch) {
+        return Some([letter, NUKTA]);
+    }
+    if vowels {
+        return SPLIT_VOWELS
+            .iter()
+            .find(|&&(vowel, _)| vowel == ch)
+            .map(|&(_, parts)| parts);
+    }
+    None
+}
+
+/// Checks whether `ch` is a letter with a nukta of its own code point
+pub(crate) fn is_precomposed_nukta(ch: char) -> bool {
+    NUKTA_LETTERS.iter().any(|&(composed, _)| composed == ch)
+}
+
+/// Checks whether `ch` followed by a nukta has a precomposed form
+pub(crate) fn has_precomposed_nukta(ch: char) -> bool {
+    NUKTA_LETTERS.iter().any(|&(_, letter)| letter == ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nukta_forms() {
+        let decomposed = "\u{09AF}\u{09BC}\u{09BE}";
+        let precomposed = "\u{09DF}\u{09BE}";
+
+        assert_eq!(Normalizer::precomposed().normalize(decomposed), precomposed);
+        assert_eq!(
+            Normalizer::precomposed().normalize(precomposed),
+            precomposed
+        );
+        assert_eq!(Normalizer::default(), Normalizer::precomposed());
+        assert_eq!(Normalizer::nfc().normalize(precomposed), decomposed);
+        assert!(Normalizer::nfc().is_normalized(decomposed));
+    }
+
+    #[test]
+    fn test_split_vowels_compose() {
+        let normalizer = Normalizer::default();
+        assert_eq!(normalizer.normalize("ক\u{09C7}\u{09BE}"), "কো");
+        assert_eq!(normalizer.normalize("ক\u{09C7}\u{09D7}"), "কৌ");
+
+        let keep_vowels = Normalizer {
+            canonical: false,
+            nukta: NuktaForm::Precomposed,
+        };
+        assert_eq!(
+            keep_vowels.normalize("ক\u{09C7}\u{09BE}"),
+            "ক\u{09C7}\u{09BE}"
+        );
+    }
+
+    #[test]
+    fn test_mark_order() {
+        // য, hasanta, nukta becomes য় followed by the hasanta
+        assert_eq!(
+            Normalizer::default().normalize("\u{09AF}\u{09CD}\u{09BC}"),
+            "\u{09DF}\u{09CD}"
+        );
+        assert_eq!(
+            Normalizer::nfc().normalize("\u{09AF}\u{09CD}\u{09BC}"),
+            "\u{09AF}\u{09BC}\u{09CD}"
+        );
+    }
+}
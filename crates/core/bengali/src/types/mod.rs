@@ -1,17 +1,18 @@
 //! Core engine types and processing functionality
 
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
 
 /// Main engine for processing keystrokes
 pub struct InputEngine {
     buffer: String,
-    mappings: HashMap<String, String>,
+    mappings: BTreeMap<String, String>,
 }
 
 impl InputEngine {
     /// Create a new engine instance with basic mappings
     pub fn new() -> Self {
-        let mut mappings = HashMap::new();
+        let mut mappings = BTreeMap::new();
         // Add some basic mappings
         mappings.insert("k".to_string(), "ক".to_string());
         mappings.insert("kh".to_string(), "খ".to_string());
@@ -25,6 +26,10 @@ impl InputEngine {
     }
 
     /// Process a single character input
+    ///
+    /// Returns the mapping for the longest match ending at `c`. When that
+    /// match extends the previous one (`k` then `h`), the result replaces
+    /// the previously returned text.
     pub fn process_char(&mut self, c: char) -> Option<String> {
         self.buffer.push(c);
         
@@ -32,8 +37,8 @@ impl InputEngine {
         let buffer = self.buffer.as_str();
         for len in (1..=buffer.len()).rev() {
             if let Some(bengali) = self.mappings.get(&buffer[buffer.len()-len..]) {
-                // Clear the matched portion from buffer
-                self.buffer = buffer[..buffer.len()-len].to_string();
+                // Keep only the matched portion so the next key can extend it
+                self.buffer = buffer[buffer.len()-len..].to_string();
                 return Some(bengali.clone());
             }
         }
@@ -42,6 +47,12 @@ impl InputEngine {
     }
 }
 
+impl Default for InputEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
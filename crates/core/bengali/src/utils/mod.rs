@@ -0,0 +1,40 @@
+//! Character classification helpers
+
+/// Hasanta (virama), which joins two consonants into a conjunct
+pub const HASANTA: char = '\u{09CD}';
+/// Zero width non-joiner, which keeps a hasanta visible
+pub const ZWNJ: char = '\u{200C}';
+/// Zero width joiner, which asks for a half or ligature form
+pub const ZWJ: char = '\u{200D}';
+/// Khanda ta, the form of ত that never takes a vowel
+pub const KHANDA_TA: char = '\u{09CE}';
+
+/// Checks whether `ch` is a Bengali consonant letter, including the nukta
+/// forms ড়, ঢ় and য়
+pub fn is_consonant(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{0995}'..='\u{09A8}'
+            | '\u{09AA}'..='\u{09B0}'
+            | '\u{09B2}'
+            | '\u{09B6}'..='\u{09B9}'
+            | '\u{09DC}'
+            | '\u{09DD}'
+            | '\u{09DF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_consonant() {
+        for ch in ['ক', 'ন', 'প', 'র', 'ল', 'শ', 'হ', '\u{09DC}', '\u{09DF}'] {
+            assert!(is_consonant(ch), "{}", ch);
+        }
+        for ch in ['অ', 'া', HASANTA, 'ং', '\u{09A9}', '\u{09B1}', 'k'] {
+            assert!(!is_consonant(ch), "{}", ch);
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Checks for malformed Bengali text
+//!
+//! Each problem is reported with the byte range of the text it concerns, so
+//! callers can point at it or fix it in place.
+
+use crate::normalize::{has_precomposed_nukta, is_precomposed_nukta, Normalizer, NuktaForm, NUKTA};
+use crate::utils::{is_consonant, HASANTA, ZWJ};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// A vowel sign that follows no consonant
+    OrphanVowelSign,
+    /// A hasanta that follows no consonant and starts no phala, as ্য does
+    OrphanHasanta,
+    /// A nukta that follows no consonant
+    OrphanNukta,
+    /// Two hasantas in a row
+    DoubleHasanta,
+    /// A letter and nukta where the precomposed letter is wanted
+    DecomposedNukta,
+    /// A precomposed letter where the letter and nukta are wanted
+    PrecomposedNukta,
+    /// ো or ৌ written as two vowel signs
+    SplitVowel,
+    /// A hasanta before a nukta, which belongs after it
+    MisorderedMarks,
+}
+
+impl DiagnosticKind {
+    /// Checks whether the problem makes the text malformed, rather than only
+    /// not normalized
+    pub fn is_malformed(&self) -> bool {
+        matches!(
+            self,
+            Self::OrphanVowelSign | Self::OrphanHasanta | Self::OrphanNukta | Self::DoubleHasanta
+        )
+    }
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::OrphanVowelSign => "vowel sign without a consonant",
+            Self::OrphanHasanta => "hasanta without a consonant",
+            Self::OrphanNukta => "nukta without a consonant",
+            Self::DoubleHasanta => "double hasanta",
+            Self::DecomposedNukta => "letter and nukta instead of the precomposed letter",
+            Self::PrecomposedNukta => "precomposed letter instead of the letter and nukta",
+            Self::SplitVowel => "two-part vowel sign written in parts",
+            Self::MisorderedMarks => "hasanta before nukta",
+        })
+    }
+}
+
+/// A problem found in the text
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    /// Byte range of the characters concerned
+    pub range: Range<usize>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}: {}", self.range.start, self.range.end, self.kind)
+    }
+}
+
+/// Finds malformed sequences in `text`, and the sequences that `normalizer`
+/// would rewrite
+pub fn validate(text: &str, normalizer: &Normalizer) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut previous: Option<(usize, char)> = None;
+    // Whether the text so far ends with a consonant, possibly with a nukta
+    // or a ZWJ as in র‍্য
+    let mut after_consonant = false;
+
+    for (index, ch) in text.char_indices() {
+        let end = index + ch.len_utf8();
+        let start = previous.map_or(index, |(start, _)| start);
+        let mut report = |kind, range| diagnostics.push(Diagnostic { kind, range });
+        // The second part of ো or ৌ
+        let split_vowel = matches!(ch, '\u{09BE}' | '\u{09D7}')
+            && previous.is_some_and(|(_, ch)| ch == '\u{09C7}');
+
+        match ch {
+            HASANTA if previous.is_some_and(|(_, ch)| ch == HASANTA) => {
+                report(DiagnosticKind::DoubleHasanta, start..end);
+            }
+            HASANTA if !after_consonant && !text[end..].starts_with(is_consonant) => {
+                report(DiagnosticKind::OrphanHasanta, index..end);
+            }
+            NUKTA => match previous {
+                Some((_, HASANTA)) if normalizer.canonical => {
+                    report(DiagnosticKind::MisorderedMarks, start..end);
+                }
+                Some((_, letter)) if is_consonant(letter) => {
+                    if has_precomposed_nukta(letter) && normalizer.nukta == NuktaForm::Precomposed {
+                        report(DiagnosticKind::DecomposedNukta, start..end);
+                    }
+                }
+                _ => report(DiagnosticKind::OrphanNukta, index..end),
+            },
+            _ if split_vowel && normalizer.canonical => {
+                report(DiagnosticKind::SplitVowel, start..end);
+            }
+            _ if split_vowel => {}
+            '\u{09BE}'..='\u{09CC}' | '\u{09D7}' if !after_consonant => {
+                report(DiagnosticKind::OrphanVowelSign, index..end);
+            }
+            _ if is_precomposed_nukta(ch) && normalizer.nukta == NuktaForm::Decomposed => {
+                report(DiagnosticKind::PrecomposedNukta, index..end);
+            }
+            _ => {}
+        }
+
+        after_consonant = is_consonant(ch) || (matches!(ch, NUKTA | ZWJ) && after_consonant);
+        previous = Some((index, ch));
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str, normalizer: &Normalizer) -> Vec<(DiagnosticKind, Range<usize>)> {
+        validate(text, normalizer)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.range))
+            .collect()
+    }
+
+    #[test]
+    fn test_well_formed_text() {
+        let normalizer = Normalizer::default();
+        for text in [
+            "আমার সোনার বাংলা",
+            "কৌশল",
+            "বন্ধু",
+            "কোথা\u{09DF}",
+            "ক্\u{200C}ল",
+            "র\u{200D}্য",
+            "্যা",
+        ] {
+            assert!(validate(text, &normalizer).is_empty(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_malformed_sequences() {
+        let normalizer = Normalizer::default();
+        assert_eq!(
+            kinds("\u{09BF}ক", &normalizer),
+            [(DiagnosticKind::OrphanVowelSign, 0..3)]
+        );
+        assert_eq!(
+            kinds("অা", &normalizer),
+            [(DiagnosticKind::OrphanVowelSign, 3..6)]
+        );
+        assert_eq!(
+            kinds("ক্্ষ", &normalizer),
+            [(DiagnosticKind::DoubleHasanta, 3..9)]
+        );
+        assert_eq!(
+            kinds(" ্", &normalizer),
+            [(DiagnosticKind::OrphanHasanta, 1..4)]
+        );
+        assert_eq!(
+            kinds("আ\u{09BC}", &normalizer),
+            [(DiagnosticKind::OrphanNukta, 3..6)]
+        );
+    }
+
+    #[test]
+    fn test_normal_form_diagnostics() {
+        let decomposed = "কোথা\u{09AF}\u{09BC}";
+        assert_eq!(
+            kinds(decomposed, &Normalizer::default()),
+            [(DiagnosticKind::DecomposedNukta, 12..18)]
+        );
+        assert!(validate(decomposed, &Normalizer::nfc()).is_empty());
+        assert_eq!(
+            kinds("কোথা\u{09DF}", &Normalizer::nfc()),
+            [(DiagnosticKind::PrecomposedNukta, 12..15)]
+        );
+        assert_eq!(
+            kinds("ক\u{09C7}\u{09BE}", &Normalizer::default()),
+            [(DiagnosticKind::SplitVowel, 3..9)]
+        );
+
+        let diagnostic = &validate("ক্্", &Normalizer::default())[0];
+        assert_eq!(diagnostic.to_string(), "3..9: double hasanta");
+        assert!(diagnostic.kind.is_malformed());
+        assert!(!DiagnosticKind::SplitVowel.is_malformed());
+    }
+}
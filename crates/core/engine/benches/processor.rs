@@ -0,0 +1,30 @@
+// crates/core/engine/benches/processor.rs
+
+//! Measures batch and keystroke conversion. Run with
+//! `cargo bench -p obadh-engine`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use obadh_engine::Processor;
+
+const TEXT: &str = "amar sonar bangla ami tomay bhalobasi. \
+    cirodin tomar akash, tomar batas, amar prane bajay bashi. \
+    o ma, fagune tor amer bone ghrane pagol kore, mori hay, hay re";
+
+fn bench_process_input(c: &mut Criterion) {
+    let mut processor = Processor::new();
+    c.bench_function("process_input", |b| {
+        b.iter(|| processor.process_input(black_box(TEXT)))
+    });
+
+    c.bench_function("push_char", |b| {
+        b.iter(|| {
+            for ch in black_box(TEXT).chars() {
+                black_box(processor.push_char(ch));
+            }
+            processor.commit()
+        })
+    });
+}
+
+criterion_group!(benches, bench_process_input);
+criterion_main!(benches);
@@ -0,0 +1,242 @@
+// crates/core/engine/build.rs
+
+//! Writes the built-in schemes out as static Rust data.
+//!
+//! `schemes/<name>.json` becomes `$OUT_DIR/<name>.rs`, a `StaticScheme`
+//! that `src/scheme` includes, so the built-in schemes load without parsing
+//! JSON, also in `no_std` builds.
+
+use serde_json::{Map, Value};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const SCHEMES: &[&str] = &["avro"];
+
+const TABLES: &[&str] = &[
+    "vowels",
+    "vowel_signs",
+    "consonants",
+    "specials",
+    "symbols",
+    "phalas",
+    "reph",
+];
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    for name in SCHEMES {
+        let path = format!("schemes/{}.json", name);
+        println!("cargo:rerun-if-changed={}", path);
+
+        let json = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+        let scheme: Value =
+            serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", path, e));
+        let code = generate(name, &scheme).unwrap_or_else(|e| panic!("{}: {}", path, e));
+        fs::write(Path::new(&out_dir).join(format!("{}.rs", name)), code).unwrap();
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+fn generate(name: &str, scheme: &Value) -> Result<String, String> {
+    let scheme = object(scheme, "scheme")?;
+    let mut code = String::new();
+    let mut line = |text: String| {
+        code.push_str(&text);
+        code.push('\n');
+    };
+
+    line(format!(
+        "// Generated by build.rs from schemes/{}.json",
+        name
+    ));
+    line(format!(
+        "static {}: StaticScheme = StaticScheme {{",
+        name.to_uppercase()
+    ));
+    line(format!("    name: {:?},", string(&scheme["name"], "name")?));
+    line(format!(
+        "    description: {:?},",
+        scheme
+            .get("description")
+            .map_or(Ok(""), |v| string(v, "description"))?
+    ));
+
+    for table in TABLES {
+        let mut entries = Vec::new();
+        if let Some(value) = scheme.get(*table) {
+            for (pattern, ch) in object(value, table)? {
+                entries.push(format!("({:?}, {:?})", pattern, single_char(ch, table)?));
+            }
+        }
+        line(format!("    {}: &[{}],", table, entries.join(", ")));
+    }
+
+    match scheme.get("conjuncts") {
+        None | Some(Value::Null) => line("    conjuncts: None,".to_string()),
+        Some(value) => {
+            let conjuncts = object(value, "conjuncts")?;
+            let standard = conjuncts.get("standard").map_or(Ok(true), |v| {
+                v.as_bool()
+                    .ok_or("`conjuncts.standard` is not a bool".to_string())
+            })?;
+            let fallback = conjuncts
+                .get("fallback")
+                .map_or(Ok("inherent"), |v| string(v, "conjuncts.fallback"))?;
+            line("    conjuncts: Some(StaticConjuncts {".to_string());
+            line(format!("        standard: {},", standard));
+            line(format!(
+                "        allow: {},",
+                strings(conjuncts.get("allow"), "allow")?
+            ));
+            line(format!(
+                "        deny: {},",
+                strings(conjuncts.get("deny"), "deny")?
+            ));
+            line(format!(
+                "        fallback: ConjunctFallback::{},",
+                variant(fallback)
+            ));
+            line("    }),".to_string());
+        }
+    }
+
+    let empty = Map::new();
+    let case = match scheme.get("case") {
+        Some(value) => object(value, "case")?,
+        None => &empty,
+    };
+    let mode = case
+        .get("mode")
+        .map_or(Ok("fold"), |v| string(v, "case.mode"))?;
+    line("    case: StaticCase {".to_string());
+    line(format!("        mode: CaseMode::{},", variant(mode)));
+    line(format!(
+        "        strict: {},",
+        strings(case.get("strict"), "strict")?
+    ));
+    line(format!(
+        "        fold: {},",
+        strings(case.get("fold"), "fold")?
+    ));
+    line("    },".to_string());
+
+    line("    rules: &[".to_string());
+    if let Some(rules) = scheme.get("rules") {
+        for (pattern, rules) in object(rules, "rules")? {
+            line(format!("        ({:?}, &[", pattern));
+            for rule in rules.as_array().ok_or(format!("rules for `{}`", pattern))? {
+                line(format!("            {},", static_rule(rule)?));
+            }
+            line("        ]),".to_string());
+        }
+    }
+    line("    ],".to_string());
+
+    line("    suggestions: &[".to_string());
+    if let Some(suggestions) = scheme.get("suggestions") {
+        for (pattern, spellings) in object(suggestions, "suggestions")? {
+            line(format!(
+                "        ({:?}, {}),",
+                pattern,
+                strings(Some(spellings), "suggestions")?
+            ));
+        }
+    }
+    line("    ],".to_string());
+    line("};".to_string());
+
+    Ok(code)
+}
+
+fn static_rule(rule: &Value) -> Result<String, String> {
+    let rule = object(rule, "rule")?;
+    let mut when = Vec::new();
+    for condition in rule
+        .get("when")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let condition = object(condition, "condition")?;
+        let value = condition
+            .get("value")
+            .map_or(Ok(""), |v| string(v, "value"))?;
+        when.push(format!(
+            "StaticCondition {{ side: Side::{}, scope: Scope::{}, value: {:?}, negate: {} }}",
+            variant(string(&condition["type"], "type")?),
+            variant(string(&condition["scope"], "scope")?),
+            value,
+            condition
+                .get("negate")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        ));
+    }
+
+    let mut output = Vec::new();
+    let chars = rule
+        .get("output")
+        .and_then(Value::as_array)
+        .ok_or("rule has no output")?;
+    for ch in chars {
+        output.push(match ch {
+            Value::String(unit) => format!("BengaliChar::{}", unit),
+            Value::Object(map) if map.len() == 1 => {
+                let (kind, ch) = map.iter().next().unwrap();
+                if kind == "Compound" {
+                    return Err("compound output is not supported".to_string());
+                }
+                format!("BengaliChar::{}({:?})", kind, single_char(ch, kind)?)
+            }
+            _ => return Err(format!("invalid rule output {}", ch)),
+        });
+    }
+
+    Ok(format!(
+        "StaticRule {{ when: &[{}], output: &[{}] }}",
+        when.join(", "),
+        output.join(", ")
+    ))
+}
+
+fn object<'a>(value: &'a Value, what: &str) -> Result<&'a Map<String, Value>, String> {
+    value
+        .as_object()
+        .ok_or(format!("`{}` is not an object", what))
+}
+
+fn string<'a>(value: &'a Value, what: &str) -> Result<&'a str, String> {
+    value.as_str().ok_or(format!("`{}` is not a string", what))
+}
+
+fn single_char(value: &Value, what: &str) -> Result<char, String> {
+    let text = string(value, what)?;
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(ch),
+        _ => Err(format!("`{}` in `{}` is not one character", text, what)),
+    }
+}
+
+/// Writes a list of strings as a static slice
+fn strings(value: Option<&Value>, what: &str) -> Result<String, String> {
+    let mut items = Vec::new();
+    if let Some(value) = value {
+        let array = value
+            .as_array()
+            .ok_or(format!("`{}` is not a list", what))?;
+        for item in array {
+            items.push(format!("{:?}", string(item, what)?));
+        }
+    }
+    Ok(format!("&[{}]", items.join(", ")))
+}
+
+/// Turns a lowercase JSON name into the Rust variant name
+fn variant(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or(String::new(), |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
@@ -0,0 +1,176 @@
+// crates/core/engine/src/autocorrect/mod.rs
+
+//! User autocorrect entries
+//!
+//! An entry maps a whole Roman word to fixed Bengali output, which replaces
+//! the rule-based conversion of that word. Entries are stored as a JSON
+//! object so users can edit them by hand:
+//!
+//! ```json
+//! { "bangladesh": "বাংলাদেশ", "dhonnobad": "ধন্যবাদ" }
+//! ```
+
+use crate::error::{Error, Result};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Autocorrect {
+    entries: BTreeMap<String, String>,
+}
+
+impl Autocorrect {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses entries from a JSON object of Roman words to Bengali text
+    pub fn from_json(json: &str) -> Result<Self> {
+        let entries: BTreeMap<String, String> = serde_json::from_str(json)?;
+        if entries.keys().any(|roman| roman.is_empty()) {
+            return Err(Error::InvalidInput(
+                "autocorrect entry with an empty word".to_string(),
+            ));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Reads entries from a JSON file
+    #[cfg(feature = "std")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Serializes the entries to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.entries)?)
+    }
+
+    /// Adds an entry, returning the Bengali text it replaced
+    pub fn insert(&mut self, roman: &str, bengali: &str) -> Option<String> {
+        if roman.is_empty() {
+            return None;
+        }
+        self.entries.insert(roman.to_string(), bengali.to_string())
+    }
+
+    /// Removes an entry, returning its Bengali text
+    pub fn remove(&mut self, roman: &str) -> Option<String> {
+        self.entries.remove(roman)
+    }
+
+    pub fn get(&self, roman: &str) -> Option<&str> {
+        self.entries.get(roman).map(String::as_str)
+    }
+
+    /// Lists the entries in order of their Roman words
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(roman, bengali)| (roman.as_str(), bengali.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Parses an Avro Phonetic autocorrect file.
+///
+/// Avro ships its autocorrect data either as a JSON object or as a text
+/// file with one entry per line, the word and its replacement separated by
+/// whitespace; lines starting with `//` or `#` are comments. Replacements
+/// are usually Roman text for the phonetic rules, so they are returned as
+/// found.
+pub fn parse_avro(text: &str) -> Result<Vec<(String, String)>> {
+    if text.trim_start().starts_with('{') {
+        let entries: BTreeMap<String, String> = serde_json::from_str(text)?;
+        return Ok(entries.into_iter().collect());
+    }
+
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") || line.starts_with('#') {
+            continue;
+        }
+
+        match line.split_once(char::is_whitespace) {
+            Some((roman, replacement)) if !replacement.trim().is_empty() => {
+                entries.push((roman.to_string(), replacement.trim().to_string()));
+            }
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "line {}: expected a word and its replacement",
+                    number + 1
+                )))
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_remove_list() {
+        let mut autocorrect = Autocorrect::new();
+        assert_eq!(autocorrect.insert("bangladesh", "বাংলাদেশ"), None);
+        assert_eq!(autocorrect.insert("ami", "আমি"), None);
+        assert_eq!(autocorrect.insert("ami", "আমী"), Some("আমি".to_string()));
+
+        let entries: Vec<_> = autocorrect.entries().collect();
+        assert_eq!(entries, [("ami", "আমী"), ("bangladesh", "বাংলাদেশ")]);
+
+        assert_eq!(autocorrect.remove("ami"), Some("আমী".to_string()));
+        assert_eq!(autocorrect.get("ami"), None);
+        assert_eq!(autocorrect.len(), 1);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut autocorrect = Autocorrect::new();
+        autocorrect.insert("bangladesh", "বাংলাদেশ");
+        let json = autocorrect.to_json().unwrap();
+        assert_eq!(Autocorrect::from_json(&json).unwrap(), autocorrect);
+
+        assert!(matches!(
+            Autocorrect::from_json(r#"{ "": "ক" }"#),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_avro() {
+        let json = r#"{ "bangladesh": "bangladesh", "thanks": "dhonnobad" }"#;
+        assert_eq!(
+            parse_avro(json).unwrap(),
+            [
+                ("bangladesh".to_string(), "bangladesh".to_string()),
+                ("thanks".to_string(), "dhonnobad".to_string()),
+            ]
+        );
+
+        let lines = "// Avro autocorrect\nbangladesh bangladesh\n\nkhoma  kShoma\n";
+        assert_eq!(
+            parse_avro(lines).unwrap(),
+            [
+                ("bangladesh".to_string(), "bangladesh".to_string()),
+                ("khoma".to_string(), "kShoma".to_string()),
+            ]
+        );
+
+        assert!(matches!(parse_avro("lonely"), Err(Error::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,236 @@
+// crates/core/engine/src/check/mod.rs
+
+//! Problems found while converting, for [`RuleSet::process_checked`]
+//!
+//! Each problem is reported with the byte range of the Roman input it comes
+//! from.
+
+use crate::processor::{input_offset, is_punctuation, RuleSet};
+use crate::trace::StepKind;
+use crate::types::{ProcessingContext, ProcessorConfig};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use obadh_bengali::validate::{self, DiagnosticKind as BengaliDiagnostic};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// A backslash with a character that forms no escape of the scheme
+    UnknownEscape,
+    /// A character that no pattern covers, copied to the output as it is
+    UnmappedCharacter,
+    /// Input whose output is not well-formed Bengali
+    InvalidBengali(BengaliDiagnostic),
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownEscape => f.write_str("unknown escape sequence"),
+            Self::UnmappedCharacter => f.write_str("character not in the scheme"),
+            Self::InvalidBengali(kind) => write!(f, "output has {}", kind),
+        }
+    }
+}
+
+/// A problem found in the input
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    /// Byte range of the input concerned
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}: {}", self.span.start, self.span.end, self.kind)
+    }
+}
+
+/// Converts a Roman segment that starts at byte `start` of the input, the
+/// way [`RuleSet::process_input`] does, and adds its problems to
+/// `diagnostics`
+pub(crate) fn convert_words(
+    rules: &RuleSet,
+    config: &ProcessorConfig,
+    text: &str,
+    start: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> String {
+    let mut output = String::new();
+    let mut context = ProcessingContext {
+        trace: Some(Vec::new()),
+        ..ProcessingContext::default()
+    };
+    // Where each space-separated word starts in the text and in the output
+    let mut words = Vec::new();
+    let mut position = 0;
+    for word in text.split_inclusive(is_punctuation) {
+        if position == 0 || text[..position].ends_with(char::is_whitespace) {
+            words.push((position, output.len()));
+        }
+        rules.push_word(config, &mut context, word, &mut output);
+        position += word.len();
+    }
+    rules.finish(config, &mut context, &mut output);
+
+    let report = |kind, span: Range<usize>, diagnostics: &mut Vec<Diagnostic>| {
+        diagnostics.push(Diagnostic {
+            kind,
+            span: input_offset(text, start, span.start)..input_offset(text, start, span.end),
+        });
+    };
+
+    let steps = context.trace.unwrap_or_default();
+    let unmapped = steps
+        .into_iter()
+        .filter(|step| step.kind == StepKind::Unmapped);
+    for span in unmapped.map(|step| step.span) {
+        match text[span.clone()].chars().next() {
+            Some('\\') => {
+                let next = text[span.end..].chars().next();
+                let end = span.end + next.map_or(0, char::len_utf8);
+                report(DiagnosticKind::UnknownEscape, span.start..end, diagnostics);
+            }
+            Some(ch) if is_unmapped(ch) => {
+                report(DiagnosticKind::UnmappedCharacter, span, diagnostics);
+            }
+            _ => {}
+        }
+    }
+
+    let normalizer = config.normalization.unwrap_or_default();
+    let ends = words
+        .iter()
+        .skip(1)
+        .copied()
+        .chain([(text.len(), output.len())]);
+    for (&(word_start, from), (word_end, to)) in words.iter().zip(ends) {
+        let span = word_start..word_start + text[word_start..word_end].trim_end().len();
+        for problem in validate::validate(&output[from..to], &normalizer) {
+            if problem.kind.is_malformed() {
+                report(
+                    DiagnosticKind::InvalidBengali(problem.kind),
+                    span.clone(),
+                    diagnostics,
+                );
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    output
+}
+
+/// Checks whether `ch` is unexpected where no pattern matched; whitespace,
+/// digits and punctuation are copied on purpose
+fn is_unmapped(ch: char) -> bool {
+    !(ch.is_whitespace() || ch.is_ascii_digit() || ch.is_ascii_punctuation())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::Corpus;
+    use crate::error::Error;
+    use crate::scheme::Scheme;
+
+    fn problems(input: &str) -> Vec<(DiagnosticKind, Range<usize>)> {
+        let rules = RuleSet::new(&Scheme::avro());
+        match rules.process_checked(&ProcessorConfig::default(), input) {
+            Ok(_) => Vec::new(),
+            Err(Error::Conversion(diagnostics)) => diagnostics
+                .into_iter()
+                .map(|diagnostic| (diagnostic.kind, diagnostic.span))
+                .collect(),
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    #[test]
+    fn test_clean_input() {
+        let rules = RuleSet::new(&Scheme::avro());
+        let config = ProcessorConfig::default();
+        let input = "amar sonar bangla, 1971. {Rust} \\{x}";
+        assert_eq!(
+            rules.process_checked(&config, input).unwrap(),
+            rules.process_input(&config, input)
+        );
+    }
+
+    #[test]
+    fn test_parity_corpus_is_clean() {
+        let rules = RuleSet::new(&Scheme::avro());
+        let config = ProcessorConfig::default();
+        for input in ["rZab", "ry", "rry"] {
+            assert!(problems(input).is_empty(), "{}", input);
+        }
+        for case in Corpus::avro().cases {
+            assert_eq!(
+                rules.process_checked(&config, &case.input).ok(),
+                Some(case.expected),
+                "{}",
+                case.input
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_escape() {
+        assert_eq!(problems("ami \\q"), [(DiagnosticKind::UnknownEscape, 4..6)]);
+        assert_eq!(problems("ami\\"), [(DiagnosticKind::UnknownEscape, 3..4)]);
+        assert!(problems("\\\\ \\$ \\{").is_empty());
+    }
+
+    #[test]
+    fn test_unmapped_characters() {
+        assert_eq!(
+            problems("ami আমি"),
+            [
+                (DiagnosticKind::UnmappedCharacter, 4..7),
+                (DiagnosticKind::UnmappedCharacter, 7..10),
+                (DiagnosticKind::UnmappedCharacter, 10..13),
+            ]
+        );
+        // Spans count the braces of earlier blocks and escapes
+        assert_eq!(
+            problems("{x} \\{ é"),
+            [(DiagnosticKind::UnmappedCharacter, 7..9)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_output() {
+        assert_eq!(
+            problems("ami \\`"),
+            [(
+                DiagnosticKind::InvalidBengali(BengaliDiagnostic::OrphanHasanta),
+                4..6
+            )]
+        );
+        // ZZ writes ্য on purpose; a hasanta alone is still an orphan
+        assert!(problems("ZZ").is_empty());
+        assert_eq!(
+            problems("\\`"),
+            [(
+                DiagnosticKind::InvalidBengali(BengaliDiagnostic::OrphanHasanta),
+                0..2
+            )]
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        let rules = RuleSet::new(&Scheme::avro());
+        let error = rules
+            .process_checked(&ProcessorConfig::default(), "a\\q")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Conversion failed: 1..3: unknown escape sequence"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+// crates/core/engine/src/corpus/mod.rs
+
+//! Golden input/output cases that pin down how a scheme converts
+//!
+//! A corpus is a JSON file of Roman input and the Bengali text it must give,
+//! so that every frontend built on the engine can check that it types the
+//! same way:
+//!
+//! ```json
+//! {
+//!   "scheme": "avro",
+//!   "description": "Reference Avro Phonetic input and output",
+//!   "cases": [
+//!     { "input": "ami", "expected": "আমি" },
+//!     { "input": "kSN", "expected": "ক্ষণ", "note": "Consonants and conjuncts" }
+//!   ]
+//! }
+//! ```
+//!
+//! [`Corpus::run`] converts every case with a scheme and reports the ones
+//! whose output differs.
+
+use crate::error::{Error, Result};
+use crate::processor::RuleSet;
+use crate::scheme::Scheme;
+use crate::session::Session;
+use crate::types::ProcessorConfig;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+const AVRO_CORPUS: &str = include_str!("../../corpus/avro.json");
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Corpus {
+    /// Name of the scheme the cases were written for
+    pub scheme: String,
+    #[serde(default)]
+    pub description: String,
+    pub cases: Vec<Case>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Case {
+    pub input: String,
+    pub expected: String,
+    /// What the case covers
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+impl Corpus {
+    /// Returns the corpus for the built-in Avro Phonetic scheme
+    pub fn avro() -> Self {
+        Self::from_json(AVRO_CORPUS).expect("built-in Avro corpus is valid")
+    }
+
+    /// Parses and validates a corpus from its JSON representation
+    pub fn from_json(json: &str) -> Result<Self> {
+        let corpus: Corpus = serde_json::from_str(json)?;
+        corpus.validate()?;
+        Ok(corpus)
+    }
+
+    /// Reads a corpus from a JSON file
+    #[cfg(feature = "std")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// Serializes the corpus back to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.scheme.is_empty() {
+            return Err(Error::InvalidInput("corpus names no scheme".to_string()));
+        }
+        if let Some(index) = self.cases.iter().position(|case| case.input.is_empty()) {
+            return Err(Error::InvalidInput(format!(
+                "corpus case {} has no input",
+                index
+            )));
+        }
+        Ok(())
+    }
+
+    /// Converts every case with `scheme`, through the batch API and
+    /// keystroke by keystroke, under the default configuration
+    pub fn run(&self, scheme: &Scheme) -> Report {
+        self.run_with_config(scheme, ProcessorConfig::default())
+    }
+
+    pub fn run_with_config(&self, scheme: &Scheme, config: ProcessorConfig) -> Report {
+        let rules = Arc::new(RuleSet::new(scheme));
+        let mut session = Session::with_config(rules, config);
+        let mut failures = Vec::new();
+
+        for (index, case) in self.cases.iter().enumerate() {
+            let batch = session.process_input(&case.input);
+
+            session.reset();
+            let mut typed = String::new();
+            for ch in case.input.chars() {
+                typed.push_str(&session.push_char(ch).commit);
+            }
+            typed.push_str(&session.commit().commit);
+
+            for (mode, actual) in [(Mode::Batch, batch), (Mode::Incremental, typed)] {
+                if actual != case.expected {
+                    failures.push(Failure {
+                        case: index,
+                        mode,
+                        input: case.input.clone(),
+                        expected: case.expected.clone(),
+                        actual,
+                    });
+                }
+            }
+        }
+
+        Report {
+            corpus: self.scheme.clone(),
+            scheme: scheme.name.clone(),
+            cases: self.cases.len(),
+            failures,
+        }
+    }
+}
+
+/// How a case was converted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// The whole input at once, as [`RuleSet::process_input`] converts it
+    Batch,
+    /// One keystroke at a time, as [`Session::push_char`] converts it
+    Incremental,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Batch => f.write_str("batch"),
+            Self::Incremental => f.write_str("incremental"),
+        }
+    }
+}
+
+/// A case whose output differs from the expected text
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Failure {
+    /// Index of the case in the corpus
+    pub case: usize,
+    pub mode: Mode,
+    pub input: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Failure {
+    /// Returns the index of the first character where the output differs
+    pub fn first_difference(&self) -> usize {
+        let mut expected = self.expected.chars();
+        let mut actual = self.actual.chars();
+        let mut index = 0;
+        while let (Some(a), Some(b)) = (expected.next(), actual.next()) {
+            if a != b {
+                break;
+            }
+            index += 1;
+        }
+        index
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let index = self.first_difference();
+        let code_point = |text: &str| {
+            text.chars()
+                .nth(index)
+                .map_or("end of text".to_string(), |ch| {
+                    format!("U+{:04X}", ch as u32)
+                })
+        };
+        writeln!(f, "case {} ({}): {}", self.case, self.mode, self.input)?;
+        writeln!(f, "  expected: {}", self.expected)?;
+        writeln!(f, "  actual:   {}", self.actual)?;
+        write!(
+            f,
+            "  character {}: expected {}, got {}",
+            index,
+            code_point(&self.expected),
+            code_point(&self.actual)
+        )
+    }
+}
+
+/// Outcome of running a corpus
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    /// Scheme named by the corpus
+    pub corpus: String,
+    /// Scheme the cases ran with
+    pub scheme: String,
+    /// Number of cases run
+    pub cases: usize,
+    pub failures: Vec<Failure>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut failed: Vec<usize> = self.failures.iter().map(|failure| failure.case).collect();
+        failed.dedup();
+        write!(
+            f,
+            "{} corpus with scheme {}: {} of {} cases passed",
+            self.corpus,
+            self.scheme,
+            self.cases - failed.len(),
+            self.cases
+        )?;
+        for failure in &self.failures {
+            write!(f, "\n{}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(cases: &[(&str, &str)]) -> Corpus {
+        Corpus {
+            scheme: "avro".to_string(),
+            description: String::new(),
+            cases: cases
+                .iter()
+                .map(|&(input, expected)| Case {
+                    input: input.to_string(),
+                    expected: expected.to_string(),
+                    note: String::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_failures() {
+        let report = corpus(&[("ami", "আমি"), ("tumi", "তুমী")]).run(&Scheme::avro());
+        assert!(!report.passed());
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].case, 1);
+        assert_eq!(report.failures[0].mode, Mode::Batch);
+        assert_eq!(report.failures[1].mode, Mode::Incremental);
+        assert_eq!(report.failures[0].actual, "তুমি");
+        assert_eq!(report.failures[0].first_difference(), 3);
+    }
+
+    #[test]
+    fn test_report_message() {
+        let report = corpus(&[("ami", "আমি"), ("ka", "ক")]).run(&Scheme::avro());
+        assert_eq!(
+            report.to_string().lines().take(5).collect::<Vec<_>>(),
+            [
+                "avro corpus with scheme avro: 1 of 2 cases passed",
+                "case 1 (batch): ka",
+                "  expected: ক",
+                "  actual:   কা",
+                "  character 1: expected end of text, got U+09BE",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let corpus = Corpus::avro();
+        assert_eq!(
+            Corpus::from_json(&corpus.to_json().unwrap()).unwrap(),
+            corpus
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_corpus() {
+        let no_scheme = r#"{"scheme": "", "cases": []}"#;
+        assert!(matches!(
+            Corpus::from_json(no_scheme),
+            Err(Error::InvalidInput(_))
+        ));
+        let no_input = r#"{"scheme": "avro", "cases": [{"input": "", "expected": ""}]}"#;
+        assert!(matches!(
+            Corpus::from_json(no_input),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,369 @@
+// crates/core/engine/src/dictionary/mod.rs
+
+//! Word lists and dictionary-backed suggestions
+//!
+//! A Roman word is expanded into every Bengali spelling the scheme tables
+//! allow for it, e.g. `i` as ই, ি, ঈ or ী. The expansion walks a trie of the
+//! word list alongside, so only spellings that start some word are followed.
+
+use crate::error::{Error, Result};
+use crate::scheme::Scheme;
+use crate::trie::{Cursor, PatternTrie};
+use crate::types::BengaliChar;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use obadh_bengali::utils::{is_consonant, HASANTA};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Cost of a spelling that matches the Roman input only ignoring case
+const FOLDED_COST: u32 = 1;
+/// Cost of a spelling from the scheme's `suggestions` table
+const SUGGESTION_COST: u32 = 2;
+/// Cost of giving two consonants typed in a row their inherent vowel
+/// instead of joining them
+const UNJOINED_COST: u32 = 1;
+
+/// A list of Bengali words with their frequencies
+#[derive(Clone, Debug, Default)]
+pub struct Dictionary {
+    words: PatternTrie<u32>,
+    len: usize,
+}
+
+impl Dictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a word list with one word per line, optionally followed by
+    /// its frequency. Empty lines and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut dictionary = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let word = fields.next().unwrap_or_default();
+            let frequency = match fields.next() {
+                Some(field) => field.parse().map_err(|_| {
+                    Error::InvalidInput(format!(
+                        "line {}: `{}` is not a frequency",
+                        number + 1,
+                        field
+                    ))
+                })?,
+                None => 0,
+            };
+            dictionary.insert(word, frequency);
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Reads a word list file in the format of [`Dictionary::parse`]
+    #[cfg(feature = "std")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Adds a word, replacing its frequency if it is already listed
+    pub fn insert(&mut self, word: &str, frequency: u32) {
+        if word.is_empty() {
+            return;
+        }
+        if self.words.get(word).is_none() {
+            self.len += 1;
+        }
+        self.words.insert(word, frequency);
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.get(word).is_some()
+    }
+
+    pub fn frequency(&self, word: &str) -> Option<u32> {
+        self.words.get(word).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Follows `text` from `cursor` in the word trie
+    fn advance(&self, cursor: Cursor, text: &str) -> Option<Cursor> {
+        text.chars()
+            .try_fold(cursor, |cursor, ch| self.words.advance(cursor, ch))
+    }
+}
+
+/// How a spelling connects to the one before it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Start {
+    Consonant,
+    /// Independent vowel, which does not follow a bare consonant
+    Vowel,
+    /// Vowel sign or phala, which needs a consonant before it
+    Dependent,
+    Other,
+}
+
+/// One Bengali spelling of a Roman pattern
+#[derive(Clone, Debug)]
+struct Spelling {
+    /// The pattern as written in the scheme, to tell exact from folded matches
+    pattern: String,
+    text: String,
+    start: Start,
+    ends_with_consonant: bool,
+    cost: u32,
+}
+
+impl Spelling {
+    fn new(pattern: &str, text: String, cost: u32) -> Option<Self> {
+        let first = text.chars().next()?;
+        let last = text.chars().next_back()?;
+        let start = match first {
+            _ if is_consonant(first) => Start::Consonant,
+            '\u{0985}'..='\u{0994}' => Start::Vowel,
+            HASANTA | '\u{09BE}'..='\u{09CC}' | '\u{09D7}' => Start::Dependent,
+            _ => Start::Other,
+        };
+
+        Some(Self {
+            pattern: pattern.to_string(),
+            ends_with_consonant: is_consonant(last),
+            text,
+            start,
+            cost,
+        })
+    }
+}
+
+/// What the word spelled so far ends with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Previous {
+    Consonant,
+    /// A consonant whose inherent vowel was typed as `o`
+    Inherent,
+    Other,
+}
+
+/// Every spelling of every scheme pattern, keyed by the lowercased pattern
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Expansions {
+    trie: PatternTrie<Vec<Spelling>>,
+}
+
+impl Expansions {
+    pub(crate) fn new(scheme: &Scheme) -> Self {
+        let mut spellings: BTreeMap<String, Vec<Spelling>> = BTreeMap::new();
+        let mut add = |pattern: &str, text: String, cost: u32| {
+            if let Some(spelling) = Spelling::new(pattern, text, cost) {
+                spellings
+                    .entry(pattern.to_lowercase())
+                    .or_default()
+                    .push(spelling);
+            }
+        };
+
+        for (pattern, entry) in scheme.mappings() {
+            for bengali_char in &entry.alternatives {
+                add(&pattern, spell(core::slice::from_ref(bengali_char)), 0);
+            }
+            for rule in &entry.rules {
+                add(&pattern, spell(&rule.output), 0);
+            }
+            if let Some(ch) = entry.phala {
+                add(&pattern, format!("{}{}", HASANTA, ch), 0);
+            }
+            if let Some(ch) = entry.reph {
+                add(&pattern, ch.to_string(), 0);
+            }
+        }
+        for (pattern, texts) in &scheme.suggestions {
+            for text in texts {
+                add(pattern, text.clone(), SUGGESTION_COST);
+            }
+        }
+
+        Self {
+            trie: spellings.into_iter().collect(),
+        }
+    }
+
+    /// Finds the words of `dictionary` that `roman` can spell, each with the
+    /// lowest cost of spelling it
+    pub(crate) fn lookup(&self, roman: &str, dictionary: &Dictionary) -> BTreeMap<String, u32> {
+        let mut search = Search {
+            expansions: self,
+            dictionary,
+            roman: roman.chars().collect(),
+            word: String::new(),
+            found: BTreeMap::new(),
+        };
+        search.walk(0, dictionary.words.root(), Previous::Other, 0);
+        search.found
+    }
+}
+
+/// State of a dictionary lookup for one Roman word
+struct Search<'a> {
+    expansions: &'a Expansions,
+    dictionary: &'a Dictionary,
+    roman: Vec<char>,
+    /// Bengali spelled so far
+    word: String,
+    found: BTreeMap<String, u32>,
+}
+
+impl Search<'_> {
+    fn walk(&mut self, pos: usize, cursor: Cursor, previous: Previous, cost: u32) {
+        if pos == self.roman.len() {
+            if self.dictionary.words.value(cursor).is_some() {
+                let best = self.found.entry(self.word.clone()).or_insert(cost);
+                *best = (*best).min(cost);
+            }
+            return;
+        }
+
+        // `o` after a consonant only keeps its inherent vowel
+        if self.roman[pos] == 'o' && previous == Previous::Consonant {
+            self.walk(pos + 1, cursor, Previous::Inherent, cost);
+        }
+
+        let trie = &self.expansions.trie;
+        let mut node = trie.root();
+        for end in pos..self.roman.len() {
+            let Some(next) = self.roman[end]
+                .to_lowercase()
+                .try_fold(node, |node, ch| trie.advance(node, ch))
+            else {
+                break;
+            };
+            node = next;
+            let Some(spellings) = trie.value(node) else {
+                continue;
+            };
+
+            let typed: String = self.roman[pos..=end].iter().collect();
+            for spelling in spellings {
+                let joins: &[(&str, u32)] = match (spelling.start, previous) {
+                    (Start::Dependent, Previous::Consonant) => &[("", 0)],
+                    (Start::Dependent, _) => continue,
+                    (Start::Vowel, Previous::Consonant) => continue,
+                    (Start::Consonant, Previous::Consonant) => &[("্", 0), ("", UNJOINED_COST)],
+                    _ => &[("", 0)],
+                };
+                let folded = if typed == spelling.pattern {
+                    0
+                } else {
+                    FOLDED_COST
+                };
+
+                for &(join, join_cost) in joins {
+                    let Some(next) = self
+                        .dictionary
+                        .advance(cursor, join)
+                        .and_then(|cursor| self.dictionary.advance(cursor, &spelling.text))
+                    else {
+                        continue;
+                    };
+
+                    let len = self.word.len();
+                    self.word.push_str(join);
+                    self.word.push_str(&spelling.text);
+                    let previous = if spelling.ends_with_consonant {
+                        Previous::Consonant
+                    } else {
+                        Previous::Other
+                    };
+                    let cost = cost + spelling.cost + folded + join_cost;
+                    self.walk(end + 1, next, previous, cost);
+                    self.word.truncate(len);
+                }
+            }
+        }
+    }
+}
+
+/// Writes a scheme output as text, joining consecutive consonants the way
+/// the processor does
+fn spell(output: &[BengaliChar]) -> String {
+    let mut text = String::new();
+    let mut after_consonant = false;
+
+    for bengali_char in output {
+        let Some(ch) = bengali_char.to_char() else {
+            continue;
+        };
+        let consonant = matches!(bengali_char, BengaliChar::Consonant(_));
+        if consonant && after_consonant {
+            text.push(HASANTA);
+        }
+        text.push(ch);
+        after_consonant = consonant;
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(roman: &str, words: &str) -> Vec<(String, u32)> {
+        let dictionary = Dictionary::parse(words).unwrap();
+        let mut found: Vec<_> = Expansions::new(&Scheme::avro())
+            .lookup(roman, &dictionary)
+            .into_iter()
+            .collect();
+        found.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        found
+    }
+
+    #[test]
+    fn test_parse_word_list() {
+        let dictionary = Dictionary::parse("# words\nআমি 120\n\nবাংলা\n").unwrap();
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.frequency("আমি"), Some(120));
+        assert_eq!(dictionary.frequency("বাংলা"), Some(0));
+        assert!(!dictionary.contains("আম"));
+
+        assert!(matches!(
+            Dictionary::parse("আমি many"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_vowel_and_sibilant_ambiguity() {
+        assert_eq!(
+            lookup("nodi", "নদী\nনদি\nনন্দী"),
+            vec![("নদি".to_string(), 0), ("নদী".to_string(), 1)]
+        );
+        assert_eq!(
+            lookup("desh", "দেশ\nদেষ\nদেস"),
+            vec![("দেশ".to_string(), 0), ("দেষ".to_string(), 1)]
+        );
+        assert_eq!(lookup("bhasa", "ভাষা"), vec![("ভাষা".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_conjuncts_and_inherent_vowel() {
+        assert_eq!(lookup("bondhu", "বন্ধু"), vec![("বন্ধু".to_string(), 0)]);
+        assert_eq!(lookup("kolom", "কলম"), vec![("কলম".to_string(), 0)]);
+        assert_eq!(lookup("klm", "কলম"), vec![("কলম".to_string(), 2)]);
+        assert_eq!(lookup("kya", "ক্যা"), vec![("ক্যা".to_string(), 0)]);
+    }
+}
@@ -1,11 +1,27 @@
 //! Error types and handling
 
+use crate::check::Diagnostic;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Invalid scheme: {0}")]
+    InvalidScheme(String),
+    #[error("Scheme error: {0}")]
+    SchemeError(#[from] serde_json::Error),
+    #[cfg(feature = "std")]
     #[error("System error: {0}")]
     SystemError(#[from] std::io::Error),
+    #[error("Conversion failed: {}", join(.0))]
+    Conversion(Vec<Diagnostic>),
+}
+
+fn join(diagnostics: &[Diagnostic]) -> String {
+    let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    messages.join("; ")
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
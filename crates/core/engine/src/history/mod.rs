@@ -0,0 +1,395 @@
+// crates/core/engine/src/history/mod.rs
+
+//! Candidates the user picked, used to rank suggestions
+//!
+//! Every pick adds one to the score of its Roman/Bengali pair, and scores
+//! halve every [`HALF_LIFE_DAYS`] days without use. The history is saved as
+//! a text file with one pair per line:
+//!
+//! ```text
+//! # obadh history 1
+//! nodi    নদী    2.500    20000
+//! ```
+//!
+//! The columns, separated by tabs, are the Roman word, the Bengali word, the
+//! score at the last use, and the day of the last use counted from the Unix
+//! epoch.
+
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Days after which an unused pick counts half as much
+pub const HALF_LIFE_DAYS: f64 = 30.0;
+/// Number of pairs kept by default
+pub const DEFAULT_LIMIT: usize = 10_000;
+
+const HEADER: &str = "# obadh history 1";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    bengali: String,
+    /// Score as of `day`
+    score: f64,
+    /// Day of the last pick, counted from the Unix epoch
+    day: u64,
+}
+
+impl Entry {
+    fn score_on(&self, day: u64) -> f64 {
+        let age = day.saturating_sub(self.day) as f64;
+        self.score * 0.5f64.powf(age / HALF_LIFE_DAYS)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct History {
+    entries: HashMap<String, Vec<Entry>>,
+    limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            limit: DEFAULT_LIMIT,
+        }
+    }
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `$XDG_DATA_HOME/obadh/history.tsv`, falling back to
+    /// `~/.local/share` when `XDG_DATA_HOME` is not set
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })?;
+        Some(data_home.join("obadh").join("history.tsv"))
+    }
+
+    /// Reads a history file; a missing file gives an empty history
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Writes the history file, creating its directory if needed
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        // Write to a temporary file first so a crash never leaves half a file
+        let temporary = path.with_extension("tsv.tmp");
+        std::fs::write(&temporary, self.to_text())?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Parses the text of a history file, keeping at most [`DEFAULT_LIMIT`]
+    /// pairs
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut history = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let entry = match fields.as_slice() {
+                [roman, bengali, score, day] if !roman.is_empty() => score
+                    .parse()
+                    .ok()
+                    .filter(|score: &f64| score.is_finite() && *score >= 0.0)
+                    .zip(day.parse().ok())
+                    .map(|(score, day)| (roman, bengali, score, day)),
+                _ => None,
+            };
+            let Some((roman, bengali, score, day)) = entry else {
+                return Err(Error::InvalidInput(format!(
+                    "history line {} is malformed",
+                    number + 1
+                )));
+            };
+
+            history
+                .entries
+                .entry(roman.to_string())
+                .or_default()
+                .push(Entry {
+                    bengali: bengali.to_string(),
+                    score,
+                    day,
+                });
+        }
+
+        history.prune(today());
+        Ok(history)
+    }
+
+    /// Serializes the history in the file format
+    pub fn to_text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .entries
+            .iter()
+            .flat_map(|(roman, entries)| {
+                entries.iter().map(move |entry| {
+                    format!(
+                        "{}\t{}\t{:.3}\t{}",
+                        roman, entry.bengali, entry.score, entry.day
+                    )
+                })
+            })
+            .collect();
+        lines.sort();
+
+        let mut text = String::from(HEADER);
+        text.push('\n');
+        for line in lines {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Sets how many pairs are kept; the lowest scores are dropped first
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.prune(today());
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Records that `bengali` was picked for `roman`
+    pub fn record(&mut self, roman: &str, bengali: &str) {
+        self.record_at(roman, bengali, SystemTime::now());
+    }
+
+    /// Records a pick made at the given time.
+    ///
+    /// Empty words and words with tabs or line breaks, which the file format
+    /// cannot hold, are not recorded.
+    pub fn record_at(&mut self, roman: &str, bengali: &str, time: SystemTime) {
+        let storable = |word: &str| !word.is_empty() && !word.contains(['\t', '\n', '\r']);
+        if !storable(roman) || !storable(bengali) {
+            return;
+        }
+
+        let day = day_of(time);
+        let entries = self.entries.entry(roman.to_string()).or_default();
+        match entries.iter_mut().find(|entry| entry.bengali == bengali) {
+            Some(entry) => {
+                entry.score = entry.score_on(day) + 1.0;
+                entry.day = entry.day.max(day);
+            }
+            None => entries.push(Entry {
+                bengali: bengali.to_string(),
+                score: 1.0,
+                day,
+            }),
+        }
+
+        self.prune(day);
+    }
+
+    /// Returns the Bengali words picked for `roman`, highest score first
+    pub fn ranked(&self, roman: &str) -> Vec<(&str, f64)> {
+        self.ranked_at(roman, SystemTime::now())
+    }
+
+    /// Returns the ranking as it stands at the given time
+    pub fn ranked_at(&self, roman: &str, time: SystemTime) -> Vec<(&str, f64)> {
+        let day = day_of(time);
+        let mut ranked: Vec<(&str, f64)> = self
+            .entries
+            .get(roman)
+            .into_iter()
+            .flatten()
+            .map(|entry| (entry.bengali.as_str(), entry.score_on(day)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked
+    }
+
+    /// Forgets every pick
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of Roman/Bengali pairs
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drops the lowest scoring pairs until the history fits its limit
+    fn prune(&mut self, day: u64) {
+        let excess = self.len().saturating_sub(self.limit);
+        if excess == 0 {
+            return;
+        }
+
+        let mut scores: Vec<f64> = self
+            .entries
+            .values()
+            .flatten()
+            .map(|entry| entry.score_on(day))
+            .collect();
+        scores.sort_by(f64::total_cmp);
+        let threshold = scores[excess - 1];
+
+        // Pairs tied at the threshold are dropped in any order
+        let mut remaining = excess;
+        for entries in self.entries.values_mut() {
+            entries.retain(|entry| {
+                let drop = remaining > 0 && entry.score_on(day) <= threshold;
+                if drop {
+                    remaining -= 1;
+                }
+                !drop
+            });
+        }
+        self.entries.retain(|_, entries| !entries.is_empty());
+    }
+}
+
+fn day_of(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / SECONDS_PER_DAY)
+}
+
+fn today() -> u64 {
+    day_of(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn day(n: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(n * SECONDS_PER_DAY)
+    }
+
+    #[test]
+    fn test_pick_ranks_first() {
+        let mut history = History::new();
+        history.record_at("nodi", "নদি", day(100));
+        history.record_at("nodi", "নদী", day(100));
+        history.record_at("nodi", "নদী", day(100));
+
+        let ranked = history.ranked_at("nodi", day(100));
+        assert_eq!(ranked, [("নদী", 2.0), ("নদি", 1.0)]);
+        assert!(history.ranked_at("ami", day(100)).is_empty());
+    }
+
+    #[test]
+    fn test_scores_decay() {
+        let mut history = History::new();
+        history.record_at("nodi", "নদী", day(100));
+        history.record_at("nodi", "নদী", day(100));
+        assert_eq!(history.ranked_at("nodi", day(130)), [("নদী", 1.0)]);
+
+        history.record_at("nodi", "নদি", day(160));
+        let ranked = history.ranked_at("nodi", day(160));
+        assert_eq!(ranked, [("নদি", 1.0), ("নদী", 0.5)]);
+    }
+
+    #[test]
+    fn test_limit_drops_lowest_scores() {
+        let mut history = History::new();
+        history.record_at("ami", "আমি", day(10));
+        history.record_at("tumi", "তুমি", day(20));
+        history.record_at("tumi", "তুমি", day(20));
+        history.record_at("se", "সে", day(20));
+
+        history.limit = 2;
+        history.prune(20);
+        assert_eq!(history.len(), 2);
+        assert!(history.ranked_at("ami", day(20)).is_empty());
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut history = History::new();
+        history.record_at("nodi", "নদী", day(20000));
+        history.record_at("ami", "আমি", day(20001));
+
+        let text = history.to_text();
+        assert_eq!(
+            text,
+            "# obadh history 1\nami\tআমি\t1.000\t20001\nnodi\tনদী\t1.000\t20000\n"
+        );
+        assert_eq!(History::parse(&text).unwrap(), history);
+        assert!(matches!(
+            History::parse("ami\tআমি\tmany\t1"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_words_that_break_lines() {
+        let mut history = History::new();
+        history.record_at("ami\t1", "আমি", day(10));
+        history.record_at("ami", "আমি\n", day(10));
+        history.record_at("ami\r", "আমি", day(10));
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_invalid_scores() {
+        for score in ["NaN", "inf", "-1.000", "-inf"] {
+            let line = format!("ami\tআমি\t{}\t1", score);
+            assert!(
+                matches!(History::parse(&line), Err(Error::InvalidInput(_))),
+                "{}",
+                score
+            );
+        }
+        assert!(History::parse("ami\tআমি\t0.000\t1").is_ok());
+    }
+
+    #[test]
+    fn test_parse_applies_limit() {
+        let text: String = (0..DEFAULT_LIMIT + 5)
+            .map(|n| format!("w{}\tআমি\t1.000\t{}\n", n, today()))
+            .collect();
+        assert_eq!(History::parse(&text).unwrap().len(), DEFAULT_LIMIT);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("obadh-history-{}", std::process::id()));
+        let path = dir.join("history.tsv");
+        assert!(History::load(&path).unwrap().is_empty());
+
+        let mut history = History::new();
+        history.record("nodi", "নদী");
+        history.save(&path).unwrap();
+        assert_eq!(History::load(&path).unwrap(), history);
+
+        history.clear();
+        assert!(history.is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,586 @@
+// crates/core/engine/src/layout/mod.rs
+
+//! Fixed keyboard layouts such as Bijoy, Probhat and National (Jatiya)
+//!
+//! A layout maps a physical key, named by the character it types on a US
+//! keyboard without modifiers, to Bengali text in each modifier layer:
+//!
+//! ```json
+//! {
+//!   "name": "bijoy",
+//!   "pre_base_reordering": true,
+//!   "reph_after_cluster": true,
+//!   "keys": {
+//!     "normal": { "j": "ক", "c": "ে", "g": "্" },
+//!     "shift": { "j": "খ", "a": "র্" },
+//!     "altgr": {},
+//!     "shift_altgr": {}
+//!   }
+//! }
+//! ```
+//!
+//! Layouts in visual order (Bijoy) take ি, ে and ৈ before the consonant they
+//! follow in Unicode, and the reph after its consonant cluster.
+
+use crate::error::{Error, Result};
+use crate::types::CompositionUpdate;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+const BIJOY_LAYOUT: &str = include_str!("../../layouts/bijoy.json");
+const NATIONAL_LAYOUT: &str = include_str!("../../layouts/national.json");
+const PROBHAT_LAYOUT: &str = include_str!("../../layouts/probhat.json");
+
+/// Names of the built-in layouts
+pub const BUILTIN_LAYOUTS: &[&str] = &["bijoy", "national", "probhat"];
+
+/// US keyboard characters typed without and with shift, by key
+const US_KEYS: &[(char, char)] = &[
+    ('`', '~'),
+    ('1', '!'),
+    ('2', '@'),
+    ('3', '#'),
+    ('4', '$'),
+    ('5', '%'),
+    ('6', '^'),
+    ('7', '&'),
+    ('8', '*'),
+    ('9', '('),
+    ('0', ')'),
+    ('-', '_'),
+    ('=', '+'),
+    ('[', '{'),
+    (']', '}'),
+    ('\\', '|'),
+    (';', ':'),
+    ('\'', '"'),
+    (',', '<'),
+    ('.', '>'),
+    ('/', '?'),
+];
+
+const REPH: &str = "র্";
+
+/// A key press: the key, named by its unmodified US character, and the
+/// modifiers held with it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyPress {
+    pub key: char,
+    pub shift: bool,
+    pub altgr: bool,
+}
+
+impl KeyPress {
+    pub fn new(key: char) -> Self {
+        Self {
+            key,
+            shift: false,
+            altgr: false,
+        }
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn with_altgr(mut self) -> Self {
+        self.altgr = true;
+        self
+    }
+
+    /// Returns the key press that types `ch` on a US keyboard
+    pub fn from_us_char(ch: char) -> Self {
+        if ch.is_ascii_uppercase() {
+            return Self::new(ch.to_ascii_lowercase()).with_shift();
+        }
+        match US_KEYS.iter().find(|&&(_, shifted)| shifted == ch) {
+            Some(&(key, _)) => Self::new(key).with_shift(),
+            None => Self::new(ch),
+        }
+    }
+
+    /// The character this key press types on a US keyboard
+    pub fn us_char(&self) -> char {
+        if !self.shift {
+            return self.key;
+        }
+        match US_KEYS.iter().find(|&&(key, _)| key == self.key) {
+            Some(&(_, shifted)) => shifted,
+            None => self.key.to_ascii_uppercase(),
+        }
+    }
+}
+
+/// Output of each key in every modifier layer
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyLayers {
+    #[serde(default)]
+    pub normal: BTreeMap<String, String>,
+    #[serde(default)]
+    pub shift: BTreeMap<String, String>,
+    #[serde(default)]
+    pub altgr: BTreeMap<String, String>,
+    #[serde(default)]
+    pub shift_altgr: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Layout {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Whether ি, ে and ৈ are typed before their consonant
+    #[serde(default)]
+    pub pre_base_reordering: bool,
+    /// Whether the reph is typed after the consonant cluster it sits on
+    #[serde(default)]
+    pub reph_after_cluster: bool,
+    pub keys: KeyLayers,
+}
+
+impl Layout {
+    pub fn bijoy() -> Self {
+        Self::from_json(BIJOY_LAYOUT).expect("built-in Bijoy layout is valid")
+    }
+
+    pub fn national() -> Self {
+        Self::from_json(NATIONAL_LAYOUT).expect("built-in National layout is valid")
+    }
+
+    pub fn probhat() -> Self {
+        Self::from_json(PROBHAT_LAYOUT).expect("built-in Probhat layout is valid")
+    }
+
+    /// Returns the built-in layout with the given name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "bijoy" => Some(Self::bijoy()),
+            "national" | "jatiya" => Some(Self::national()),
+            "probhat" => Some(Self::probhat()),
+            _ => None,
+        }
+    }
+
+    /// Parses and validates a layout from its JSON representation
+    pub fn from_json(json: &str) -> Result<Self> {
+        let layout: Layout = serde_json::from_str(json)?;
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Reads a layout from a JSON file
+    #[cfg(feature = "std")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// Serializes the layout back to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Returns the text a key press types, if the layout maps it
+    pub fn output(&self, key: KeyPress) -> Option<&str> {
+        let layer = match (key.shift, key.altgr) {
+            (false, false) => &self.keys.normal,
+            (true, false) => &self.keys.shift,
+            (false, true) => &self.keys.altgr,
+            (true, true) => &self.keys.shift_altgr,
+        };
+        layer
+            .get(key.key.encode_utf8(&mut [0; 4]) as &str)
+            .map(String::as_str)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(Error::InvalidScheme("layout name is empty".to_string()));
+        }
+
+        let layers = [
+            ("normal", &self.keys.normal),
+            ("shift", &self.keys.shift),
+            ("altgr", &self.keys.altgr),
+            ("shift_altgr", &self.keys.shift_altgr),
+        ];
+        for (layer, keys) in layers {
+            for (key, text) in keys {
+                if key.chars().count() != 1 || text.is_empty() {
+                    return Err(Error::InvalidScheme(format!(
+                        "key `{}` in `{}` of layout `{}` must be one character with output",
+                        key, layer, self.name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Input engine for fixed layouts.
+///
+/// It has the same composition model as the phonetic
+/// [`Processor`](crate::Processor): keys build a word in the preedit, and
+/// whitespace or punctuation commits it. [`Session::set_layout`] switches a
+/// session over to one.
+///
+/// [`Session::set_layout`]: crate::Session::set_layout
+#[derive(Clone, Debug)]
+pub struct LayoutProcessor {
+    layout: Layout,
+    /// Keys of the word being composed
+    keys: Vec<KeyPress>,
+}
+
+impl LayoutProcessor {
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            keys: Vec::new(),
+        }
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Converts text typed on a US keyboard, as if each character were
+    /// typed with this layout
+    pub fn process_input(&self, input: &str) -> String {
+        let keys: Vec<KeyPress> = input.chars().map(KeyPress::from_us_char).collect();
+        self.render(&keys)
+    }
+
+    /// Adds one key press to the current composition
+    pub fn push_key(&mut self, key: KeyPress) -> CompositionUpdate {
+        self.keys.push(key);
+
+        let ends_word = self
+            .text_of(key)
+            .chars()
+            .all(|ch| ch.is_whitespace() || ch.is_ascii_punctuation() || ch == '।');
+        if ends_word {
+            return self.commit();
+        }
+
+        self.composition_update(String::new())
+    }
+
+    /// Removes the last key press of the current composition.
+    ///
+    /// Returns `None` when there is nothing to remove.
+    pub fn backspace(&mut self) -> Option<CompositionUpdate> {
+        self.keys.pop()?;
+        Some(self.composition_update(String::new()))
+    }
+
+    /// Finalizes the current composition and hands it over for insertion
+    pub fn commit(&mut self) -> CompositionUpdate {
+        let output = self.preedit();
+        self.reset();
+        self.composition_update(output)
+    }
+
+    /// Discards the current composition without committing it
+    pub fn reset(&mut self) {
+        self.keys.clear();
+    }
+
+    pub fn preedit(&self) -> String {
+        self.render(&self.keys)
+    }
+
+    fn composition_update(&self, commit: String) -> CompositionUpdate {
+        let preedit = self.preedit();
+        let cursor = preedit.chars().count();
+        CompositionUpdate {
+            preedit,
+            commit,
+            cursor,
+        }
+    }
+
+    /// Text of a key press; unmapped keys type their US character
+    fn text_of(&self, key: KeyPress) -> String {
+        match self.layout.output(key) {
+            Some(text) => text.to_string(),
+            None => key.us_char().to_string(),
+        }
+    }
+
+    /// Converts a sequence of key presses to Unicode text in logical order
+    fn render(&self, keys: &[KeyPress]) -> String {
+        let mut state = RenderState::default();
+        for &key in keys {
+            self.render_text(&self.text_of(key), &mut state);
+        }
+        state.flush_sign();
+        state.output
+    }
+
+    fn render_text(&self, text: &str, state: &mut RenderState) {
+        let mut chars = text.chars();
+        let (Some(first), rest) = (chars.next(), chars.as_str()) else {
+            return;
+        };
+
+        if is_vowel_sign(first) && rest.is_empty() && state.after_link() {
+            // A hasanta with no consonant before it makes the vowel sign an
+            // independent vowel, as the link key does in Bijoy
+            state.output.pop();
+            state.output.push(independent_vowel(first).unwrap_or(first));
+            return;
+        }
+
+        if self.layout.pre_base_reordering && rest.is_empty() && is_pre_base_sign(first) {
+            state.flush_sign();
+            state.pending_sign = Some(first);
+            state.sign_has_base = false;
+            return;
+        }
+
+        if text == REPH && self.layout.reph_after_cluster {
+            match state.cluster {
+                Some(start) => state.output.insert_str(start, REPH),
+                None => state.output.push_str(REPH),
+            }
+            return;
+        }
+
+        if is_consonant(first) {
+            // A hasanta before it continues the cluster; anything else ends
+            // the previous one
+            if !state.output.ends_with('্') || state.cluster.is_none() {
+                if state.sign_has_base {
+                    state.flush_sign();
+                }
+                state.sign_has_base = state.pending_sign.is_some();
+                state.cluster = Some(state.output.len());
+            }
+            state.output.push_str(text);
+            return;
+        }
+
+        if first == '্' {
+            state.output.push_str(text);
+            return;
+        }
+
+        if is_vowel_sign(first) && rest.is_empty() {
+            if let Some(sign) = state.pending_sign.take() {
+                // ে typed before the consonant and া or ৗ after it
+                match (sign, first) {
+                    ('ে', 'া') => state.output.push('ো'),
+                    ('ে', 'ৗ') => state.output.push('ৌ'),
+                    _ => {
+                        state.output.push(sign);
+                        state.output.push(first);
+                    }
+                }
+                return;
+            }
+
+            match (state.output.chars().next_back(), first) {
+                (Some('ে'), 'া') => {
+                    state.output.pop();
+                    state.output.push('ো');
+                }
+                (Some('ে'), 'ৗ') => {
+                    state.output.pop();
+                    state.output.push('ৌ');
+                }
+                (Some('অ'), 'া') => {
+                    state.output.pop();
+                    state.output.push('আ');
+                }
+                // Layouts without separate vowel keys (Probhat) type the
+                // vowel sign where no consonant comes before it
+                (previous, _) if !previous.is_some_and(carries_sign) => {
+                    state.output.push(independent_vowel(first).unwrap_or(first));
+                }
+                _ => state.output.push(first),
+            }
+            return;
+        }
+
+        state.flush_sign();
+        state.cluster = None;
+        state.output.push_str(text);
+    }
+}
+
+#[derive(Debug, Default)]
+struct RenderState {
+    output: String,
+    /// Pre-base vowel sign waiting for the consonant cluster it follows
+    pending_sign: Option<char>,
+    /// Whether a consonant has been typed after the pending sign
+    sign_has_base: bool,
+    /// Byte offset where the last consonant cluster starts
+    cluster: Option<usize>,
+}
+
+impl RenderState {
+    fn flush_sign(&mut self) {
+        if let Some(sign) = self.pending_sign.take() {
+            self.output.push(sign);
+        }
+        self.sign_has_base = false;
+    }
+
+    /// Checks whether the output ends with a hasanta that follows no
+    /// consonant
+    fn after_link(&self) -> bool {
+        let mut previous = self.output.chars().rev();
+        previous.next() == Some('্') && !previous.next().is_some_and(is_consonant)
+    }
+}
+
+fn is_consonant(ch: char) -> bool {
+    obadh_bengali::utils::is_consonant(ch) || ch == 'ৎ'
+}
+
+fn is_vowel_sign(ch: char) -> bool {
+    matches!(ch, '\u{09BE}'..='\u{09CC}' | '\u{09D7}')
+}
+
+/// Checks whether a vowel sign typed after `ch` attaches to it
+fn carries_sign(ch: char) -> bool {
+    is_consonant(ch) || matches!(ch, '্' | '\u{09BC}')
+}
+
+fn is_pre_base_sign(ch: char) -> bool {
+    matches!(ch, 'ি' | 'ে' | 'ৈ')
+}
+
+fn independent_vowel(sign: char) -> Option<char> {
+    match sign {
+        'া' => Some('আ'),
+        'ি' => Some('ই'),
+        'ী' => Some('ঈ'),
+        'ু' => Some('উ'),
+        'ূ' => Some('ঊ'),
+        'ৃ' => Some('ঋ'),
+        'ে' => Some('এ'),
+        'ৈ' => Some('ঐ'),
+        'ো' => Some('ও'),
+        'ৌ' | 'ৗ' => Some('ঔ'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bijoy(input: &str) -> String {
+        LayoutProcessor::new(Layout::bijoy()).process_input(input)
+    }
+
+    #[test]
+    fn test_builtin_layouts_load() {
+        for name in BUILTIN_LAYOUTS {
+            let layout = Layout::builtin(name).unwrap();
+            assert_eq!(&layout.name, name);
+        }
+        assert_eq!(Layout::bijoy().output(KeyPress::new('j')), Some("ক"));
+        assert_eq!(
+            Layout::bijoy().output(KeyPress::new('j').with_shift()),
+            Some("খ")
+        );
+        assert!(Layout::builtin("qwerty").is_none());
+    }
+
+    #[test]
+    fn test_key_press_from_us_char() {
+        assert_eq!(KeyPress::from_us_char('J'), KeyPress::new('j').with_shift());
+        assert_eq!(KeyPress::from_us_char('&'), KeyPress::new('7').with_shift());
+        assert_eq!(KeyPress::from_us_char('|').us_char(), '|');
+        assert_eq!(KeyPress::new('q').with_shift().us_char(), 'Q');
+    }
+
+    #[test]
+    fn test_bijoy_pre_base_reordering() {
+        assert_eq!(bijoy("cj"), "কে");
+        assert_eq!(bijoy("dj"), "কি");
+        assert_eq!(bijoy("cjf"), "কো");
+        assert_eq!(bijoy("cjX"), "কৌ");
+        assert_eq!(bijoy("cjgN"), "ক্ষে");
+        assert_eq!(bijoy("cjZ"), "ক্যে");
+        assert_eq!(bijoy("jcj"), "ককে");
+        assert_eq!(bijoy("c"), "ে");
+    }
+
+    #[test]
+    fn test_bijoy_conjuncts_and_reph() {
+        assert_eq!(bijoy("jgN"), "ক্ষ");
+        assert_eq!(bijoy("Lm"), "ধম");
+        assert_eq!(bijoy("LmA"), "ধর্ম");
+        assert_eq!(bijoy("jz"), "ক্র");
+        assert_eq!(bijoy("gf"), "আ");
+        assert_eq!(bijoy("gd"), "ই");
+        assert_eq!(bijoy("Ff"), "আ");
+        assert_eq!(bijoy("hvsJ"), "বরুখ");
+        assert_eq!(bijoy("jZf"), "ক্যা");
+    }
+
+    #[test]
+    fn test_national_and_probhat_logical_order() {
+        let national = LayoutProcessor::new(Layout::national());
+        assert_eq!(national.process_input("jc"), "কে");
+        assert_eq!(national.process_input("Aj"), "র্ক");
+        assert_eq!(national.process_input("jcf"), "কো");
+
+        let probhat = LayoutProcessor::new(Layout::probhat());
+        assert_eq!(probhat.process_input("k["), "কে");
+        assert_eq!(probhat.process_input("k/S"), "ক্ষ");
+        assert_eq!(probhat.process_input("ami"), "আমি");
+        assert_eq!(probhat.process_input("kai"), "কাই");
+        assert_eq!(probhat.process_input("ki a"), "কি আ");
+    }
+
+    #[test]
+    fn test_altgr_layer() {
+        let mut processor = LayoutProcessor::new(Layout::national());
+        processor.push_key(KeyPress::new('f').with_altgr());
+        let update = processor.push_key(KeyPress::new('m'));
+        assert_eq!(update.preedit, "আম");
+        let update = processor.push_key(KeyPress::new('d').with_shift().with_altgr());
+        assert_eq!(update.preedit, "আমঈ");
+    }
+
+    #[test]
+    fn test_composition() {
+        let mut processor = LayoutProcessor::new(Layout::bijoy());
+        let update = processor.push_key(KeyPress::from_us_char('c'));
+        assert_eq!(update.preedit, "ে");
+        let update = processor.push_key(KeyPress::from_us_char('j'));
+        assert_eq!(update.preedit, "কে");
+
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "ে");
+        processor.push_key(KeyPress::from_us_char('h'));
+
+        let update = processor.push_key(KeyPress::new(' '));
+        assert_eq!(update.commit, "বে ");
+        assert!(update.preedit.is_empty());
+        assert!(processor.backspace().is_none());
+    }
+
+    #[test]
+    fn test_rejects_invalid_layout() {
+        let json = r#"{ "name": "x", "keys": { "normal": { "ab": "ক" } } }"#;
+        assert!(matches!(
+            Layout::from_json(json),
+            Err(Error::InvalidScheme(_))
+        ));
+    }
+}
@@ -1,8 +1,35 @@
 // crates/core/engine/src/lib.rs
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod autocorrect;
+pub mod check;
+pub mod corpus;
+pub mod dictionary;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod history;
+pub mod layout;
+pub mod processor;
+pub mod reverse;
+pub mod scheme;
+pub mod session;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod trace;
+pub mod trie;
 pub mod types;
 pub mod utils;
-pub mod processor;
 
 // Re-export main types
-pub use processor::Processor;
\ No newline at end of file
+pub use autocorrect::Autocorrect;
+pub use corpus::Corpus;
+pub use dictionary::Dictionary;
+#[cfg(feature = "std")]
+pub use history::History;
+pub use layout::{Layout, LayoutProcessor};
+pub use processor::{Processor, RuleSet};
+pub use reverse::Reverser;
+pub use scheme::Scheme;
+pub use session::Session;
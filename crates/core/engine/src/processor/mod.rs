@@ -1,272 +1,808 @@
 // crates/core/engine/src/processor/mod.rs
 
-use crate::types::{BengaliChar, ProcessingContext};
-use std::collections::HashMap;
+use crate::autocorrect;
+use crate::autocorrect::Autocorrect;
+use crate::check::{self, Diagnostic};
+use crate::dictionary::{Dictionary, Expansions};
+use crate::error::{Error, Result};
+#[cfg(feature = "std")]
+use crate::history::History;
+use crate::layout::{KeyPress, Layout};
+use crate::scheme::{
+    ConjunctFallback, ConjunctPolicy, ConjunctSet, Pattern, Resolution, Scheme, MAX_PREFIX_LEN,
+};
+use crate::session::Session;
+use crate::trace::{self, StepKind, Trace};
+use crate::trie::{Cursor, PatternTrie};
+use crate::types::{BengaliChar, Candidate, CandidateSource, ProcessingContext, ProcessorConfig};
+use crate::types::{CommittedWord, CompositionUpdate};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use obadh_bengali::utils::{KHANDA_TA, ZWJ, ZWNJ};
+#[cfg(feature = "std")]
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+};
 
+/// A compiled scheme with the word lists used alongside it.
+///
+/// A rule set does not change once built, so one `Arc<RuleSet>` can back any
+/// number of [`Session`]s, also across threads.
+#[derive(Clone, Debug)]
+pub struct RuleSet {
+    trie: PatternTrie<Pattern>,
+    /// Whether the folded walk can find anything under the case policy
+    fold_case: bool,
+    /// Consonant pairs that may be joined; `None` joins every pair
+    conjuncts: Option<ConjunctSet>,
+    /// Spellings tried when looking words up in the dictionary
+    expansions: Expansions,
+    dictionary: Dictionary,
+    /// Whole words converted to fixed output before the rules run
+    autocorrect: Autocorrect,
+}
+
+/// A [`Session`] with its own rule set and selection history.
+///
+/// The history, file input and streams need the `std` feature; without it a
+/// processor only composes and converts.
 pub struct Processor {
-    mappings: HashMap<String, Vec<BengaliChar>>,
-    context: ProcessingContext,
+    session: Session,
+    /// Candidates picked before, which rank first
+    #[cfg(feature = "std")]
+    history: History,
+}
+
+/// Outcome of matching the start of the pending input
+enum Match<'a> {
+    Found(usize, Resolution<'a>),
+    /// The match depends on input that has not been typed yet
+    NeedInput,
+    None,
 }
 
 impl Processor {
+    /// Creates a processor using the built-in Avro Phonetic scheme
     pub fn new() -> Self {
-        let mut mappings: HashMap<String, Vec<BengaliChar>> = HashMap::new();
+        Self::with_scheme(&Scheme::avro())
+    }
 
-        // Vowels and Vowel Signs according to Avro mappings
-        mappings.insert("o".to_string(), vec![BengaliChar::Vowel('অ')]);
-        mappings.insert(
-            "a".to_string(),
-            vec![BengaliChar::Vowel('আ'), BengaliChar::VowelSign('া')],
-        );
-        mappings.insert(
-            "i".to_string(),
-            vec![BengaliChar::Vowel('ই'), BengaliChar::VowelSign('ি')],
-        );
-        mappings.insert(
-            "I".to_string(),
-            vec![BengaliChar::Vowel('ঈ'), BengaliChar::VowelSign('ী')],
-        );
-        mappings.insert(
-            "u".to_string(),
-            vec![BengaliChar::Vowel('উ'), BengaliChar::VowelSign('ু')],
-        );
-        mappings.insert(
-            "U".to_string(),
-            vec![BengaliChar::Vowel('ঊ'), BengaliChar::VowelSign('ূ')],
-        );
-        mappings.insert(
-            "rri".to_string(),
-            vec![BengaliChar::Vowel('ঋ'), BengaliChar::VowelSign('ৃ')],
-        );
-        mappings.insert(
-            "e".to_string(),
-            vec![BengaliChar::Vowel('এ'), BengaliChar::VowelSign('ে')],
-        );
-        mappings.insert(
-            "OI".to_string(),
-            vec![BengaliChar::Vowel('ঐ'), BengaliChar::VowelSign('ৈ')],
-        );
-        mappings.insert(
-            "O".to_string(),
-            vec![BengaliChar::Vowel('ও'), BengaliChar::VowelSign('ো')],
-        );
-        mappings.insert(
-            "OU".to_string(),
-            vec![BengaliChar::Vowel('ঔ'), BengaliChar::VowelSign('ৌ')],
-        );
+    /// Creates a processor for the given transliteration scheme
+    pub fn with_scheme(scheme: &Scheme) -> Self {
+        Self::with_config(scheme, ProcessorConfig::default())
+    }
 
-        // Consonants (both uppercase and lowercase as per Avro mappings)
-        mappings.extend(Self::create_consonant_mappings());
+    /// Creates a processor for the given scheme and configuration
+    pub fn with_config(scheme: &Scheme, config: ProcessorConfig) -> Self {
+        Self {
+            session: Session::with_config(Arc::new(RuleSet::new(scheme)), config),
+            #[cfg(feature = "std")]
+            history: History::default(),
+        }
+    }
 
-        // Special Characters with Modifier '\'
-        mappings.insert("\\^".to_string(), vec![BengaliChar::Special('ঁ')]); // Chandrabindu
-        mappings.insert("\\`".to_string(), vec![BengaliChar::Special('্')]); // Hasanta
-        mappings.insert("\\\\".to_string(), vec![BengaliChar::Symbol('\\')]); // Backslash
-        mappings.insert("\\$".to_string(), vec![BengaliChar::Special('৳')]); // Taka symbol
+    /// Creates a processor from a scheme file
+    #[cfg(feature = "std")]
+    pub fn from_scheme_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::with_scheme(&Scheme::from_file(path)?))
+    }
 
-        Self {
-            mappings,
-            context: ProcessingContext {
-                previous: None,
-                previous_output: None,
-                prevent_conjunct: false,
-            },
-        }
-    }
-
-    /// Creates consonant mappings including uppercase and lowercase letters
-    fn create_consonant_mappings() -> HashMap<String, Vec<BengaliChar>> {
-        let mut consonant_mappings = HashMap::new();
-
-        // Avro consonant mappings
-        let consonants = vec![
-            ("k", 'ক'),
-            ("kh", 'খ'),
-            ("g", 'গ'),
-            ("gh", 'ঘ'),
-            ("Ng", 'ঙ'),
-            ("c", 'চ'),
-            ("ch", 'ছ'),
-            ("j", 'জ'),
-            ("jh", 'ঝ'),
-            ("NG", 'ঞ'),
-            ("T", 'ট'),
-            ("Th", 'ঠ'),
-            ("D", 'ড'),
-            ("Dh", 'ঢ'),
-            ("N", 'ণ'),
-            ("t", 'ত'),
-            ("th", 'থ'),
-            ("d", 'দ'),
-            ("dh", 'ধ'),
-            ("n", 'ন'),
-            ("p", 'প'),
-            ("ph", 'ফ'),
-            ("f", 'ফ'),
-            ("b", 'ব'),
-            ("bh", 'ভ'),
-            ("v", 'ভ'),
-            ("m", 'ম'),
-            ("z", 'য'),
-            ("r", 'র'),
-            ("l", 'ল'),
-            ("sh", 'শ'),
-            ("S", 'শ'),
-            ("Sh", 'ষ'),
-            ("s", 'স'),
-            ("h", 'হ'),
-            ("R", 'ড়'),
-            ("Rh", 'ঢ়'),
-            ("y", 'য়'),
-        ];
-
-        for &(roman, bengali_char) in &consonants {
-            consonant_mappings.insert(
-                roman.to_string(),
-                vec![BengaliChar::Consonant(bengali_char)],
-            );
-        }
-
-        consonant_mappings
+    pub fn config(&self) -> &ProcessorConfig {
+        self.session.config()
+    }
+
+    /// Changes the configuration; it applies from the next keystroke on
+    pub fn set_config(&mut self, config: ProcessorConfig) {
+        self.session.set_config(config);
+    }
+
+    /// Switches between phonetic input and a fixed keyboard layout; see
+    /// [`Session::set_layout`]
+    pub fn set_layout(&mut self, layout: Option<Layout>) {
+        self.session.set_layout(layout);
+    }
+
+    /// Returns the fixed layout in use, if any
+    pub fn layout(&self) -> Option<&Layout> {
+        self.session.layout()
+    }
+
+    /// Sets the word list used for candidate suggestions
+    pub fn set_dictionary(&mut self, dictionary: Dictionary) {
+        self.session.rules_mut().dictionary = dictionary;
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        self.session.rules().dictionary()
+    }
+
+    /// Replaces the autocorrect entries
+    pub fn set_autocorrect(&mut self, autocorrect: Autocorrect) {
+        self.session.rules_mut().autocorrect = autocorrect;
+    }
+
+    pub fn autocorrect(&self) -> &Autocorrect {
+        self.session.rules().autocorrect()
+    }
+
+    /// Gives access to the autocorrect entries for adding and removing them
+    pub fn autocorrect_mut(&mut self) -> &mut Autocorrect {
+        &mut self.session.rules_mut().autocorrect
+    }
+
+    /// Imports an Avro Phonetic autocorrect file, returning the number of
+    /// entries added.
+    ///
+    /// Replacements written in Roman are converted with this processor's
+    /// rules; Bengali ones are kept as they are.
+    pub fn import_avro_autocorrect(&mut self, text: &str) -> Result<usize> {
+        let entries = autocorrect::parse_avro(text)?;
+        let count = entries.len();
+        for (roman, replacement) in entries {
+            let bengali = if replacement.is_ascii() {
+                self.convert(&replacement)
+            } else {
+                replacement
+            };
+            self.autocorrect_mut().insert(&roman, &bengali);
+        }
+        Ok(count)
+    }
+
+    /// Replaces the selection history, e.g. with one loaded from disk
+    #[cfg(feature = "std")]
+    pub fn set_history(&mut self, history: History) {
+        self.history = history;
+    }
+
+    #[cfg(feature = "std")]
+    pub fn history(&self) -> &History {
+        &self.history
     }
 
+    /// Records that the user picked `bengali` for `roman`
+    #[cfg(feature = "std")]
+    pub fn record_selection(&mut self, roman: &str, bengali: &str) {
+        self.history.record(roman, bengali);
+    }
+
+    /// Forgets every recorded selection
+    #[cfg(feature = "std")]
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Returns the Bengali words that `roman` may stand for, best first.
+    ///
+    /// Words picked before come first, by their history score, followed by
+    /// the candidates of [`Session::candidates`].
+    pub fn candidates(&self, roman: &str) -> Vec<Candidate> {
+        let mut candidates = self.session.candidates(roman);
+        let picked = self.picked(roman);
+        candidates.retain(|candidate| !picked.iter().any(|pick| pick.text == candidate.text));
+        picked.into_iter().chain(candidates).collect()
+    }
+
+    /// Returns the words picked for `roman` before, best first
+    #[cfg(feature = "std")]
+    fn picked(&self, roman: &str) -> Vec<Candidate> {
+        self.history
+            .ranked(roman)
+            .into_iter()
+            .map(|(text, _)| Candidate {
+                text: text.to_string(),
+                source: CandidateSource::History,
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn picked(&self, _roman: &str) -> Vec<Candidate> {
+        Vec::new()
+    }
+
+    /// Returns the candidates for the word being composed
+    pub fn composition_candidates(&self) -> Vec<Candidate> {
+        self.candidates(self.session.roman())
+    }
+
+    /// Converts a complete Roman string to Bengali; see
+    /// [`RuleSet::process_input`]
     pub fn process_input(&mut self, input: &str) -> String {
+        self.session.process_input(input)
+    }
+
+    /// Converts a complete Roman string, failing if any of it did not
+    /// convert cleanly; see [`RuleSet::process_checked`]
+    pub fn process_checked(&self, input: &str) -> Result<String> {
+        self.session.process_checked(input)
+    }
+
+    /// Converts a complete Roman string, recording how; see
+    /// [`RuleSet::trace`]
+    pub fn trace(&self, input: &str) -> Trace {
+        self.session.trace(input)
+    }
+
+    /// Converts Roman text read from `reader` and writes it to `writer`;
+    /// see [`convert_stream`](crate::stream::convert_stream)
+    #[cfg(feature = "std")]
+    pub fn convert_stream(&self, reader: impl BufRead, writer: impl Write) -> Result<()> {
+        self.session.convert_stream(reader, writer)
+    }
+
+    /// Converts a complete Roman string on a fresh context
+    pub(crate) fn convert(&self, input: &str) -> String {
+        self.session.rules().convert(self.config(), input)
+    }
+
+    /// Adds one keystroke to the current composition; see
+    /// [`Session::push_char`]
+    pub fn push_char(&mut self, ch: char) -> CompositionUpdate {
+        self.session.push_char(ch)
+    }
+
+    /// Adds one key press to the current composition; see
+    /// [`Session::push_key`]
+    pub fn push_key(&mut self, key: KeyPress) -> CompositionUpdate {
+        self.session.push_key(key)
+    }
+
+    /// Removes the end of the current composition; see
+    /// [`Session::backspace`]
+    pub fn backspace(&mut self) -> Option<CompositionUpdate> {
+        self.session.backspace()
+    }
+
+    /// Finalizes the current composition and hands it over for insertion
+    pub fn commit(&mut self) -> CompositionUpdate {
+        self.session.commit()
+    }
+
+    /// Commits `text` in place of the current composition, usually one of
+    /// its candidates, and records the pick in the history
+    pub fn select_candidate(&mut self, text: &str) -> CompositionUpdate {
+        #[cfg(feature = "std")]
+        self.history.record(self.session.roman(), text);
+        self.session.select_candidate(text)
+    }
+
+    /// Returns the word committed by the last keystroke, until another
+    /// keystroke arrives
+    pub fn last_commit(&self) -> Option<&CommittedWord> {
+        self.session.last_commit()
+    }
+
+    /// Gives back the last committed word so that its Roman input can
+    /// replace it; see [`Session::revert_last_commit`]
+    pub fn revert_last_commit(&mut self) -> Option<CommittedWord> {
+        self.session.revert_last_commit()
+    }
+
+    /// Discards the current composition without committing it
+    pub fn reset(&mut self) {
+        self.session.reset();
+    }
+
+    /// Returns the preedit that the current composition would display
+    pub fn preedit(&self) -> String {
+        self.session.preedit()
+    }
+}
+
+impl RuleSet {
+    /// Compiles `scheme`, with no dictionary or autocorrect entries
+    pub fn new(scheme: &Scheme) -> Self {
+        Self {
+            trie: scheme.mappings().into_iter().collect(),
+            fold_case: scheme.case.folds_any(),
+            conjuncts: scheme.conjuncts.as_ref().map(ConjunctPolicy::compile),
+            expansions: Expansions::new(scheme),
+            dictionary: Dictionary::default(),
+            autocorrect: Autocorrect::default(),
+        }
+    }
+
+    /// Sets the word list used for candidate suggestions
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.dictionary = dictionary;
+        self
+    }
+
+    /// Sets the autocorrect entries
+    pub fn with_autocorrect(mut self, autocorrect: Autocorrect) -> Self {
+        self.autocorrect = autocorrect;
+        self
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    pub fn autocorrect(&self) -> &Autocorrect {
+        &self.autocorrect
+    }
+
+    /// Returns the Bengali words that `roman` may stand for, best first.
+    ///
+    /// An autocorrect entry comes first, then dictionary words ranked by how
+    /// closely the Roman input spells them and by frequency. The rule-based
+    /// conversion comes last unless it is already listed.
+    pub fn candidates(&self, config: &ProcessorConfig, roman: &str) -> Vec<Candidate> {
+        let mut words: Vec<(String, u32)> = self
+            .expansions
+            .lookup(roman, &self.dictionary)
+            .into_iter()
+            .collect();
+        words.sort_by(|(a, a_cost), (b, b_cost)| {
+            let a_frequency = self.dictionary.frequency(a);
+            let b_frequency = self.dictionary.frequency(b);
+            a_cost
+                .cmp(b_cost)
+                .then_with(|| b_frequency.cmp(&a_frequency))
+                .then_with(|| a.cmp(b))
+        });
+
+        let autocorrected = self.autocorrect.get(roman);
+        let mut candidates: Vec<Candidate> = autocorrected
+            .map(|text| Candidate {
+                text: text.to_string(),
+                source: CandidateSource::Autocorrect,
+            })
+            .into_iter()
+            .chain(
+                words
+                    .into_iter()
+                    .filter(|(text, _)| Some(text.as_str()) != autocorrected)
+                    .map(|(text, _)| Candidate {
+                        text,
+                        source: CandidateSource::Dictionary,
+                    }),
+            )
+            .collect();
+
+        let converted = self.convert(config, roman);
+        if !candidates
+            .iter()
+            .any(|candidate| candidate.text == converted)
+        {
+            candidates.push(Candidate {
+                text: converted,
+                source: CandidateSource::Rules,
+            });
+        }
+        candidates
+    }
+
+    /// Converts a complete Roman string to Bengali.
+    ///
+    /// The conversion runs on its own context, so it does not disturb a
+    /// composition in progress. Words with an autocorrect entry are replaced
+    /// before the rules see them.
+    ///
+    /// Text in braces is kept as it is, without the braces: `ami {Rust}
+    /// shikhi` gives `আমি Rust শিখি`. A block that is never closed runs to
+    /// the end of the input, and `\{` writes a literal brace.
+    pub fn process_input(&self, config: &ProcessorConfig, input: &str) -> String {
+        self.convert_text(config, input, None)
+    }
+
+    /// Converts a complete Roman string like [`RuleSet::process_input`], but
+    /// fails with [`Error::Conversion`] if any of it did not convert cleanly
+    pub fn process_checked(&self, config: &ProcessorConfig, input: &str) -> Result<String> {
+        let mut diagnostics = Vec::new();
+        let output = self.convert_text(config, input, Some(&mut diagnostics));
+        if diagnostics.is_empty() {
+            Ok(output)
+        } else {
+            Err(Error::Conversion(diagnostics))
+        }
+    }
+
+    /// Converts a complete Roman string like [`RuleSet::process_input`],
+    /// recording which pattern and branch produced each part of the output
+    pub fn trace(&self, config: &ProcessorConfig, input: &str) -> Trace {
+        trace::trace(self, config, input)
+    }
+
+    /// Converts the Roman segments of `input`, adding what looks wrong in
+    /// them to `diagnostics` if given
+    fn convert_text(
+        &self,
+        config: &ProcessorConfig,
+        input: &str,
+        mut diagnostics: Option<&mut Vec<Diagnostic>>,
+    ) -> String {
+        let mut output = String::new();
+        for (start, segment) in split_verbatim(input) {
+            match segment {
+                Segment::Roman(text) => {
+                    let converted = match diagnostics.as_deref_mut() {
+                        Some(diagnostics) => {
+                            check::convert_words(self, config, &text, start, diagnostics)
+                        }
+                        None => self.convert_words(config, &text),
+                    };
+                    output.push_str(&normalized(config, converted));
+                }
+                Segment::Verbatim(text) => output.push_str(text),
+            }
+        }
+        output
+    }
+
+    /// Converts Roman text with autocorrect on a fresh context, so that a
+    /// pending consonant is finished before any verbatim text that follows
+    fn convert_words(&self, config: &ProcessorConfig, input: &str) -> String {
+        if self.autocorrect.is_empty() {
+            return self.convert(config, input);
+        }
+
         let mut output = String::new();
-        let mut pending_consonant: Option<String> = None;
-        let mut index = 0;
-        let chars: Vec<char> = input.chars().collect();
+        let mut context = ProcessingContext::default();
+        for word in input.split_inclusive(is_punctuation) {
+            self.push_word(config, &mut context, word, &mut output);
+        }
+        self.finish(config, &mut context, &mut output);
 
-        while index < chars.len() {
-            let mut matched = false;
+        output
+    }
 
-            // Handle spaces and punctuation
-            if is_punctuation(chars[index]) {
-                if let Some(consonant_str) = pending_consonant.take() {
-                    output.push_str(&self.process_pending_consonant(&consonant_str, false));
+    /// Converts one word with the space or punctuation mark that ends it, or
+    /// writes its autocorrect entry.
+    ///
+    /// Words end where [`Session::push_char`] commits them, so that batch and
+    /// incremental input pick the same autocorrect entries.
+    pub(crate) fn push_word(
+        &self,
+        config: &ProcessorConfig,
+        context: &mut ProcessingContext,
+        word: &str,
+        output: &mut String,
+    ) {
+        match self.autocorrected(config, word) {
+            Some(text) => {
+                self.finish(config, context, output);
+                context.begin_step(StepKind::Autocorrect, word.len(), output.len(), None);
+                output.push_str(&text);
+                context.end_step(output.len());
+                // Later prefix rules still see the Roman word
+                *context = ProcessingContext {
+                    offset: context.offset,
+                    trace: context.trace.take(),
+                    ..ProcessingContext::default()
+                };
+                context.pending_input.push_str(word);
+                self.consume_input(context, word.len());
+            }
+            None => {
+                for ch in word.chars() {
+                    self.feed(config, context, ch, output);
                 }
-                output.push(chars[index]);
-                index += 1;
-                self.context.previous = None;
-                continue;
             }
+        }
+    }
+
+    /// Converts a complete Roman string on a fresh context
+    pub(crate) fn convert(&self, config: &ProcessorConfig, input: &str) -> String {
+        let mut output = String::new();
+        let mut context = ProcessingContext::default();
 
-            // Try to find the longest possible match
-            for i in (1..=5).rev() {
-                if index + i > chars.len() {
-                    continue;
+        for ch in input.chars() {
+            self.feed(config, &mut context, ch, &mut output);
+        }
+        self.finish(config, &mut context, &mut output);
+
+        output
+    }
+
+    /// Returns the autocorrect output for a Roman word, which may end with
+    /// punctuation and whitespace that are converted as usual
+    pub(crate) fn autocorrected(&self, config: &ProcessorConfig, roman: &str) -> Option<String> {
+        if let Some(text) = self.autocorrect.get(roman) {
+            return Some(text.to_string());
+        }
+
+        let word = roman.trim_end_matches(is_punctuation);
+        if word.is_empty() || word.len() == roman.len() {
+            return None;
+        }
+        let text = self.autocorrect.get(word)?;
+        Some(format!(
+            "{}{}",
+            text,
+            self.convert(config, &roman[word.len()..])
+        ))
+    }
+
+    /// Feeds one Roman character, appending whatever output became final
+    pub(crate) fn feed(
+        &self,
+        config: &ProcessorConfig,
+        context: &mut ProcessingContext,
+        ch: char,
+        output: &mut String,
+    ) {
+        context.pending_input.push(ch);
+
+        // Wait while the input can still grow into a longer mapping
+        while !context.pending_input.is_empty() && !self.has_longer_match(&context.pending_input) {
+            if !self.consume_pending_input(config, context, output, false) {
+                // A rule needs to see more of the following input
+                break;
+            }
+        }
+    }
+
+    /// Resolves all pending input and the pending consonant
+    pub(crate) fn finish(
+        &self,
+        config: &ProcessorConfig,
+        context: &mut ProcessingContext,
+        output: &mut String,
+    ) {
+        while !context.pending_input.is_empty() {
+            self.consume_pending_input(config, context, output, true);
+        }
+        let pending = context.pending_consonant.is_some();
+        if pending {
+            context.begin_step(StepKind::Flush, 0, output.len(), None);
+        }
+        self.flush_pending_consonant(context, output);
+        if pending {
+            context.end_step(output.len());
+        }
+    }
+
+    /// Converts the longest mapped prefix of the pending input.
+    ///
+    /// Returns `false` without consuming anything when the match depends on
+    /// input that has not arrived yet; at the end of input (`at_end`) the
+    /// missing input counts as a word boundary.
+    fn consume_pending_input(
+        &self,
+        config: &ProcessorConfig,
+        context: &mut ProcessingContext,
+        output: &mut String,
+        at_end: bool,
+    ) -> bool {
+        let (end, resolution) = match self.resolve_match(context, at_end) {
+            Match::Found(end, resolution) => (end, resolution),
+            Match::NeedInput => return false,
+            Match::None => {
+                // Output the unmatched character as-is, or as its Bengali
+                // form if the config asks for one
+                if let Some(ch) = context.pending_input.chars().next() {
+                    context.begin_step(StepKind::Unmapped, ch.len_utf8(), output.len(), None);
+                    self.consume_input(context, ch.len_utf8());
+                    self.flush_pending_consonant(context, output);
+                    output.push(config.convert(ch));
+                    context.previous = None;
+                    context.end_step(output.len());
                 }
+                return true;
+            }
+        };
 
-                let test_str: String = chars[index..index + i].iter().collect();
+        let pattern = context
+            .trace
+            .is_some()
+            .then(|| self.pattern_key(&context.pending_input[..end]));
+        let kind = match resolution {
+            Resolution::Alternatives(_) => StepKind::Mapping,
+            Resolution::Sequence(_) => StepKind::Rule,
+            Resolution::Phala(_) => StepKind::Phala,
+            Resolution::Reph(_) => StepKind::Reph,
+        };
 
-                // Handle symbol transliteration with backslash
-                if test_str.starts_with('\\') {
-                    if let Some(consonant_str) = pending_consonant.take() {
-                        output.push_str(&self.process_pending_consonant(&consonant_str, false));
-                    }
-                    let bengali_chars_option = self.mappings.get(&test_str).cloned();
-                    if let Some(bengali_chars) = bengali_chars_option {
-                        for bengali_char in bengali_chars {
-                            output.push(bengali_char.to_char());
-                        }
-                        index += i;
-                        matched = true;
-                        self.context.previous = None;
-                        break;
-                    } else {
-                        // Output the backslash and continue
-                        output.push('\\');
-                        index += 1;
-                        matched = true;
-                        break;
-                    }
+        // Special handling for 'o' as combining blocker
+        let is_o = &context.pending_input[..end] == "o";
+
+        if is_o && context.pending_consonant.is_some() {
+            // Do not output 'অ' here; it is implicit
+            context.begin_step(StepKind::Blocker, end, output.len(), pattern);
+            self.consume_input(context, end);
+            context.prevent_conjunct = true;
+            context.previous = None;
+            return true;
+        }
+
+        context.begin_step(kind, end, output.len(), pattern);
+        self.consume_input(context, end);
+        match resolution {
+            Resolution::Alternatives(bengali_chars) => {
+                self.handle_bengali_chars(bengali_chars, context, output);
+            }
+            Resolution::Sequence(bengali_chars) => {
+                for bengali_char in bengali_chars {
+                    self.handle_bengali_chars(core::slice::from_ref(bengali_char), context, output);
+                }
+            }
+            Resolution::Phala(ch) => self.attach_phala(ch, context),
+            Resolution::Reph(ch) => {
+                // A reph never joins the consonant before it
+                self.flush_pending_consonant(context, output);
+                context.pending_consonant = Some(ch.to_string());
+                context.previous = Some(BengaliChar::Consonant(ch));
+                if let Some(step) = context.step() {
+                    step.chars.push(BengaliChar::Consonant(ch));
                 }
+            }
+        }
+        context.end_step(output.len());
+        true
+    }
 
-                // Case-insensitive matching for consonants
-                let test_str_lower = test_str.to_lowercase();
+    /// Returns the scheme pattern that matched `input`, which differs from it
+    /// in case after a case-folded match
+    fn pattern_key(&self, input: &str) -> String {
+        if self.trie.get(input).is_some() {
+            input.to_string()
+        } else {
+            input.to_lowercase()
+        }
+    }
 
-                let bengali_chars_option = if let Some(bengali_chars) = self.mappings.get(&test_str)
-                {
-                    Some(bengali_chars.clone())
-                } else if let Some(bengali_chars) = self.mappings.get(&test_str_lower) {
-                    if self.is_case_sensitive_consonant(&test_str) {
-                        None
-                    } else {
-                        Some(bengali_chars.clone())
-                    }
-                } else {
-                    None
-                };
+    /// Moves `len` bytes of pending input to the converted tail
+    fn consume_input(&self, context: &mut ProcessingContext, len: usize) {
+        context.offset += len;
+        context
+            .previous_input
+            .extend(context.pending_input.drain(..len));
 
-                if let Some(bengali_chars) = bengali_chars_option {
-                    matched = true;
-                    index += i;
+        let excess = context
+            .previous_input
+            .chars()
+            .count()
+            .saturating_sub(MAX_PREFIX_LEN);
+        if excess > 0 {
+            let cut = context
+                .previous_input
+                .char_indices()
+                .nth(excess)
+                .map_or(context.previous_input.len(), |(i, _)| i);
+            context.previous_input.drain(..cut);
+        }
+    }
 
-                    // Special handling for 'o' as combining blocker
-                    if test_str == "o" && pending_consonant.is_some() {
-                        // Set prevent_conjunct flag to true
-                        self.context.prevent_conjunct = true;
-                        // Do not output 'অ' here; it is implicit
-                        break;
-                    }
+    /// Picks the longest pattern at the start of the pending input whose
+    /// rules or tables apply in the current context
+    fn resolve_match(&self, context: &ProcessingContext, at_end: bool) -> Match<'_> {
+        let input = context.pending_input.as_str();
+        let after_consonant = context.pending_consonant.is_some() && !context.prevent_conjunct;
+        let mut limit = input.len();
 
-                    self.handle_bengali_chars(
-                        &bengali_chars,
-                        &mut pending_consonant,
-                        &mut output,
-                        test_str.as_str(),
-                    );
-                    break;
-                }
+        while let Some((end, pattern)) = self.longest_match(input, limit) {
+            let suffix = &input[end..];
+            if !at_end && suffix.chars().count() < pattern.lookahead {
+                return Match::NeedInput;
+            }
+            // Each phala attaches to a cluster once, so that repeating it
+            // does not stack more of them
+            let repeated = pattern
+                .phala
+                .zip(context.pending_consonant.as_deref())
+                .is_some_and(|(ch, cluster)| has_phala(cluster, ch));
+            if let Some(resolution) = pattern.resolve(
+                &context.previous_input,
+                suffix,
+                after_consonant && !repeated,
+            ) {
+                return Match::Found(end, resolution);
+            }
+            limit = end - 1;
+        }
+
+        Match::None
+    }
+
+    /// Finds the longest pattern at the start of `input`, ending at or before
+    /// byte `limit`, in a single walk.
+    ///
+    /// The input is matched exactly and, in parallel, case-folded so that
+    /// e.g. `K` finds `k`. An exact match wins over a folded one of the same
+    /// length, and a folded match counts only if the scheme's case policy
+    /// lets the pattern fold.
+    fn longest_match(&self, input: &str, limit: usize) -> Option<(usize, &Pattern)> {
+        let mut exact = Some(self.trie.root());
+        let mut folded = Some(self.trie.root());
+        let mut best = None;
+
+        for (i, ch) in input.char_indices() {
+            let end = i + ch.len_utf8();
+            if end > limit {
+                break;
+            }
+            exact = exact.and_then(|cursor| self.trie.advance(cursor, ch));
+            folded = folded.and_then(|cursor| self.advance_folded(cursor, ch));
+            if exact.is_none() && folded.is_none() {
+                break;
             }
 
-            if !matched {
-                // Output any pending consonant before handling the unmatched character
-                if let Some(consonant_str) = pending_consonant.take() {
-                    output.push_str(&self.process_pending_consonant(&consonant_str, false));
+            if let Some(pattern) = exact.and_then(|cursor| self.trie.value(cursor)) {
+                best = Some((end, pattern));
+            } else if let Some(pattern) = folded.and_then(|cursor| self.trie.value(cursor)) {
+                if pattern.folds {
+                    best = Some((end, pattern));
                 }
-                // Output the unmatched character as-is
-                output.push(chars[index]);
-                index += 1;
-                self.context.previous = None;
             }
         }
 
-        // Output any pending consonant at the end
-        if let Some(consonant_str) = pending_consonant.take() {
-            output.push_str(&self.process_pending_consonant(&consonant_str, false));
+        best
+    }
+
+    /// Checks whether some mapping is longer than `prefix` and starts with it
+    fn has_longer_match(&self, prefix: &str) -> bool {
+        let mut exact = Some(self.trie.root());
+        let mut folded = Some(self.trie.root());
+
+        for ch in prefix.chars() {
+            exact = exact.and_then(|cursor| self.trie.advance(cursor, ch));
+            folded = folded.and_then(|cursor| self.advance_folded(cursor, ch));
         }
 
-        output
+        [exact, folded]
+            .into_iter()
+            .flatten()
+            .any(|cursor| self.trie.has_continuation(cursor))
+    }
+
+    fn advance_folded(&self, cursor: Cursor, ch: char) -> Option<Cursor> {
+        if !self.fold_case {
+            return None;
+        }
+        ch.to_lowercase()
+            .try_fold(cursor, |cursor, lower| self.trie.advance(cursor, lower))
     }
 
     fn handle_bengali_chars(
-        &mut self,
-        bengali_chars: &Vec<BengaliChar>,
-        pending_consonant: &mut Option<String>,
+        &self,
+        bengali_chars: &[BengaliChar],
+        context: &mut ProcessingContext,
         output: &mut String,
-        input_str: &str,
     ) {
-        let bengali_char = self.select_bengali_char(bengali_chars);
+        let bengali_char = self.select_bengali_char(bengali_chars, context);
+        if let Some(step) = context.step() {
+            step.chars.push(bengali_char.clone());
+        }
 
         match bengali_char {
             BengaliChar::Consonant(ch) => {
-                if let Some(prev_consonant) = pending_consonant.take() {
-                    if self.context.prevent_conjunct {
+                if let Some(prev_consonant) = context.pending_consonant.take() {
+                    let fallback = self.conjunct_fallback(&prev_consonant, ch);
+                    let prevent_conjunct = context.prevent_conjunct;
+                    if let Some(step) = context.step() {
+                        step.conjunct_blocked = prevent_conjunct;
+                        step.conjunct_fallback = fallback.filter(|_| !prevent_conjunct);
+                        step.hasanta =
+                            !prevent_conjunct && fallback != Some(ConjunctFallback::Inherent);
+                    }
+                    if context.prevent_conjunct || fallback == Some(ConjunctFallback::Inherent) {
                         // Output previous consonant with inherent vowel
                         output.push_str(&self.process_pending_consonant(&prev_consonant, true));
-                        self.context.prevent_conjunct = false;
+                        context.prevent_conjunct = false;
+                    } else if fallback == Some(ConjunctFallback::Zwnj) {
+                        // Keep the hasanta visible instead of forming a ligature
+                        output.push_str(&prev_consonant);
+                        output.push('্');
+                        output.push(ZWNJ);
                     } else {
                         // Insert hasanta to form conjunct
-                        output.push_str(&format!("{}্", prev_consonant));
+                        output.push_str(&prev_consonant);
+                        output.push('্');
                     }
                 }
-                pending_consonant.replace(ch.to_string());
-                self.context.previous = Some(BengaliChar::Consonant(ch));
+                context.pending_consonant = Some(ch.to_string());
+                context.previous = Some(BengaliChar::Consonant(ch));
             }
             BengaliChar::VowelSign(ch) => {
-                if let Some(consonant_str) = pending_consonant.take() {
-                    let combined = format!("{}{}", consonant_str, ch);
-                    output.push_str(&combined);
+                if let Some(consonant_str) = context.pending_consonant.take() {
+                    output.push_str(&consonant_str);
+                    output.push(ch);
+                    context.prevent_conjunct = false;
                 } else {
                     // Vowel sign without consonant, treat as independent vowel
                     if let Some(ind_vowel) = self.vowel_sign_to_independent_vowel(ch) {
@@ -275,33 +811,85 @@ impl Processor {
                         output.push(ch);
                     }
                 }
-                self.context.previous = None;
+                context.previous = None;
             }
             BengaliChar::Vowel(ch) => {
-                if let Some(consonant_str) = pending_consonant.take() {
-                    output.push_str(&self.process_pending_consonant(&consonant_str, false));
-                }
+                self.flush_pending_consonant(context, output);
                 output.push(ch);
-                self.context.previous = None;
+                context.previous = None;
             }
             BengaliChar::Special(ch) | BengaliChar::Symbol(ch) => {
-                if let Some(consonant_str) = pending_consonant.take() {
-                    output.push_str(&self.process_pending_consonant(&consonant_str, false));
-                }
+                self.flush_pending_consonant(context, output);
                 output.push(ch);
-                self.context.previous = None;
+                context.previous = None;
             }
             BengaliChar::Compound(chars) => {
-                if let Some(consonant_str) = pending_consonant.take() {
-                    output.push_str(&self.process_pending_consonant(&consonant_str, false));
-                }
+                self.flush_pending_consonant(context, output);
                 let compound_str: String = chars.iter().collect();
                 output.push_str(&compound_str);
-                self.context.previous = None;
+                context.previous = None;
+            }
+            BengaliChar::Zwnj | BengaliChar::Zwj => {
+                let joiner = if bengali_char == BengaliChar::Zwnj {
+                    ZWNJ
+                } else {
+                    ZWJ
+                };
+                // After 'o' the consonant keeps its vowel and the joiner
+                // only separates it from what follows
+                if !context.prevent_conjunct {
+                    if let Some(consonant_str) = context.pending_consonant.take() {
+                        output.push_str(&consonant_str);
+                        output.push('্');
+                        if let Some(step) = context.step() {
+                            step.hasanta = true;
+                        }
+                    }
+                }
+                self.flush_pending_consonant(context, output);
+                output.push(joiner);
+                context.previous = None;
+            }
+            BengaliChar::KhandaTa => {
+                self.flush_pending_consonant(context, output);
+                output.push(KHANDA_TA);
+                context.previous = None;
             }
         }
     }
 
+    /// Returns the fallback to use when `pending` may not join `next`
+    fn conjunct_fallback(&self, pending: &str, next: char) -> Option<ConjunctFallback> {
+        let conjuncts = self.conjuncts.as_ref()?;
+        let last = pending.chars().last()?;
+        (!conjuncts.allows(last, next)).then_some(conjuncts.fallback)
+    }
+
+    /// Attaches a phala to the pending consonant, which then takes the
+    /// following vowel sign as a whole
+    fn attach_phala(&self, ch: char, context: &mut ProcessingContext) {
+        if let Some(pending) = context.pending_consonant.as_mut() {
+            // Ya-phala after র needs ZWJ, otherwise the র renders as a reph
+            if ch == 'য' && pending.ends_with('র') {
+                pending.push(ZWJ);
+            }
+            pending.push('্');
+            pending.push(ch);
+        }
+        if let Some(step) = context.step() {
+            step.chars.push(BengaliChar::Consonant(ch));
+            step.hasanta = true;
+        }
+        context.previous = Some(BengaliChar::Consonant(ch));
+    }
+
+    fn flush_pending_consonant(&self, context: &mut ProcessingContext, output: &mut String) {
+        if let Some(consonant_str) = context.pending_consonant.take() {
+            output.push_str(&self.process_pending_consonant(&consonant_str, false));
+        }
+        context.prevent_conjunct = false;
+    }
+
     fn process_pending_consonant(&self, consonant_str: &str, with_inherent_vowel: bool) -> String {
         if with_inherent_vowel {
             consonant_str.to_string()
@@ -311,10 +899,14 @@ impl Processor {
         }
     }
 
-    fn select_bengali_char(&self, bengali_chars: &Vec<BengaliChar>) -> BengaliChar {
+    fn select_bengali_char(
+        &self,
+        bengali_chars: &[BengaliChar],
+        context: &ProcessingContext,
+    ) -> BengaliChar {
         // Implement selection logic based on context
         // If previous is a consonant, prefer VowelSign
-        if let Some(BengaliChar::Consonant(_)) = &self.context.previous {
+        if let Some(BengaliChar::Consonant(_)) = &context.previous {
             for bengali_char in bengali_chars {
                 if matches!(bengali_char, BengaliChar::VowelSign(_)) {
                     return bengali_char.clone();
@@ -346,27 +938,589 @@ impl Processor {
             _ => None,
         }
     }
+}
 
-    fn is_case_sensitive_consonant(&self, s: &str) -> bool {
-        // Consonants where case matters (e.g., 't' vs 'T')
-        let case_sensitive_consonants = vec!["t", "T", "d", "D", "n", "N", "s", "S", "r", "R"];
-        case_sensitive_consonants.contains(&s)
+impl Default for Processor {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-fn is_punctuation(c: char) -> bool {
+/// Checks whether `cluster` has `ch` attached with a hasanta
+fn has_phala(cluster: &str, ch: char) -> bool {
+    cluster
+        .chars()
+        .zip(cluster.chars().skip(1))
+        .any(|pair| pair == ('্', ch))
+}
+
+/// Applies the configured normalization, if any
+pub(crate) fn normalized(config: &ProcessorConfig, text: String) -> String {
+    match &config.normalization {
+        Some(normalizer) => normalizer.normalize(&text),
+        None => text,
+    }
+}
+
+pub(crate) fn is_punctuation(c: char) -> bool {
     c.is_ascii_punctuation() || c.is_whitespace()
 }
 
-impl BengaliChar {
-    fn to_char(&self) -> char {
-        match self {
-            BengaliChar::Vowel(ch)
-            | BengaliChar::Consonant(ch)
-            | BengaliChar::VowelSign(ch)
-            | BengaliChar::Special(ch)
-            | BengaliChar::Symbol(ch) => *ch,
-            BengaliChar::Compound(_) => '\0', // Not applicable for single char conversion
+/// A part of the input to [`Processor::process_input`]
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Segment<'a> {
+    Roman(String),
+    /// Text of a `{...}` block, without the braces
+    Verbatim(&'a str),
+}
+
+/// Splits the input at `{...}` blocks, turning `\{` into a literal brace.
+///
+/// Each segment comes with the byte offset of its start in the input.
+pub(crate) fn split_verbatim(input: &str) -> Vec<(usize, Segment<'_>)> {
+    let mut segments = Vec::new();
+    let mut roman = String::new();
+    let mut start = 0;
+    // Byte offset of the input not split yet
+    let mut position = 0;
+
+    while let Some(open) = input[position..].find('{').map(|i| position + i) {
+        // `\\` is a backslash of its own, so only an odd run escapes
+        let before = &input[position..open];
+        let backslashes = before.len() - before.trim_end_matches('\\').len();
+        if backslashes % 2 == 1 {
+            roman.push_str(&input[position..open - 1]);
+            roman.push('{');
+            position = open + 1;
+            continue;
         }
+
+        roman.push_str(&input[position..open]);
+        if !roman.is_empty() {
+            segments.push((start, Segment::Roman(core::mem::take(&mut roman))));
+        }
+        let close = input[open + 1..]
+            .find('}')
+            .map_or(input.len(), |i| open + 1 + i);
+        segments.push((open + 1, Segment::Verbatim(&input[open + 1..close])));
+        position = (close + 1).min(input.len());
+        start = position;
+    }
+
+    roman.push_str(&input[position..]);
+    if !roman.is_empty() {
+        segments.push((start, Segment::Roman(roman)));
     }
-}
\ No newline at end of file
+    segments
+}
+
+/// Returns the input offset of byte `i` of a Roman segment that starts at
+/// byte `start` of the input; each `{` in the segment stands for `\{`
+pub(crate) fn input_offset(text: &str, start: usize, i: usize) -> usize {
+    start + i + text[..i].matches('{').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::{CaseMode, CasePolicy};
+    use crate::types::BackspaceMode;
+    use obadh_bengali::normalize::Normalizer;
+
+    fn type_keys(processor: &mut Processor, keys: &str) -> Vec<CompositionUpdate> {
+        keys.chars().map(|ch| processor.push_char(ch)).collect()
+    }
+
+    #[test]
+    fn test_process_input() {
+        let mut processor = Processor::new();
+        assert_eq!(processor.process_input("ami"), "আমি");
+        assert_eq!(processor.process_input("kobita"), "কবিতা");
+        assert_eq!(processor.process_input("bondhu"), "বন্ধু");
+        assert_eq!(processor.process_input("koi"), "কই");
+        assert_eq!(processor.process_input("ami tumi"), "আমি তুমি");
+    }
+
+    #[test]
+    fn test_preedit_follows_keystrokes() {
+        let mut processor = Processor::new();
+        let updates = type_keys(&mut processor, "kha");
+
+        assert_eq!(updates[0].preedit, "ক");
+        assert_eq!(updates[1].preedit, "খ");
+        assert_eq!(updates[2].preedit, "খা");
+        assert_eq!(updates[2].cursor, 2);
+        assert!(updates.iter().all(|update| update.commit.is_empty()));
+    }
+
+    #[test]
+    fn test_punctuation_commits_word() {
+        let mut processor = Processor::new();
+        type_keys(&mut processor, "ami");
+        let update = processor.push_char(' ');
+
+        assert_eq!(update.commit, "আমি ");
+        assert_eq!(update.preedit, "");
+        assert_eq!(update.cursor, 0);
+    }
+
+    #[test]
+    fn test_incremental_matches_batch() {
+        let input = "amar sonar bangla, ami tomay bhalobasi.";
+        let mut processor = Processor::new();
+
+        let mut committed: String = type_keys(&mut processor, input)
+            .into_iter()
+            .map(|update| update.commit)
+            .collect();
+        committed.push_str(&processor.commit().commit);
+
+        assert_eq!(committed, processor.process_input(input));
+    }
+
+    #[test]
+    fn test_backspace_reconverts_word() {
+        let mut processor = Processor::new();
+        type_keys(&mut processor, "kh");
+
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "ক");
+
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "");
+        assert!(processor.backspace().is_none());
+    }
+
+    #[test]
+    fn test_backspace_keeps_conjunct_prefix() {
+        let mut processor = Processor::new();
+        type_keys(&mut processor, "bondh");
+        assert_eq!(processor.preedit(), "বন্ধ");
+
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "বন্দ");
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "বন");
+    }
+
+    #[test]
+    fn test_backspace_whole_graphemes() {
+        let mut processor = Processor::new();
+        processor.set_config(ProcessorConfig {
+            backspace: BackspaceMode::Grapheme,
+            ..ProcessorConfig::default()
+        });
+
+        type_keys(&mut processor, "amar");
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "আমা");
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "আ");
+
+        processor.reset();
+        type_keys(&mut processor, "bondhu");
+        assert_eq!(processor.preedit(), "বন্ধু");
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "ব");
+        let update = processor.backspace().unwrap();
+        assert_eq!(update.preedit, "");
+        assert!(processor.backspace().is_none());
+    }
+
+    #[test]
+    fn test_revert_last_commit() {
+        let mut processor = Processor::new();
+        let updates = type_keys(&mut processor, "ami ");
+        assert_eq!(updates[3].commit, "আমি ");
+        assert!(processor.backspace().is_none());
+
+        let reverted = processor.revert_last_commit().unwrap();
+        assert_eq!(reverted.roman, "ami ");
+        assert_eq!(reverted.bengali, "আমি ");
+        assert!(processor.revert_last_commit().is_none());
+
+        // Any other keystroke forgets the commit
+        type_keys(&mut processor, "tumi ");
+        processor.push_char('k');
+        assert!(processor.last_commit().is_none());
+
+        processor.reset();
+        type_keys(&mut processor, "nodi");
+        processor.select_candidate("নদী");
+        assert_eq!(processor.last_commit().unwrap().roman, "nodi");
+    }
+
+    #[test]
+    fn test_verbatim_blocks() {
+        let mut processor = Processor::new();
+        assert_eq!(
+            processor.process_input("ami {Rust} shikhi"),
+            "আমি Rust শিখি"
+        );
+        // A pending consonant keeps its inherent vowel at the boundary
+        assert_eq!(processor.process_input("k{ab}"), "কab");
+        assert_eq!(processor.process_input("{API}er"), "APIএর");
+        // An unterminated block runs to the end of the input
+        assert_eq!(processor.process_input("ami {hello"), "আমি hello");
+        assert_eq!(processor.process_input("ami {}"), "আমি ");
+        assert_eq!(processor.process_input("a\\{b}"), "আ{ব}");
+        assert_eq!(processor.process_input("ami \\\\{x}"), "আমি \\x");
+        assert_eq!(processor.process_input("a\\\\\\{b}"), "আ\\{ব}");
+    }
+
+    #[test]
+    fn test_split_verbatim() {
+        assert_eq!(
+            split_verbatim("a{b}c{d"),
+            [
+                (0, Segment::Roman("a".to_string())),
+                (2, Segment::Verbatim("b")),
+                (4, Segment::Roman("c".to_string())),
+                (6, Segment::Verbatim("d")),
+            ]
+        );
+        assert_eq!(
+            split_verbatim("\\{x}"),
+            [(0, Segment::Roman("{x}".to_string()))]
+        );
+        assert_eq!(
+            split_verbatim("\\\\{x}"),
+            [
+                (0, Segment::Roman("\\\\".to_string())),
+                (3, Segment::Verbatim("x")),
+            ]
+        );
+        assert!(split_verbatim("").is_empty());
+    }
+
+    #[test]
+    fn test_commit_and_reset() {
+        let mut processor = Processor::new();
+        type_keys(&mut processor, "bon");
+        assert_eq!(processor.commit().commit, "বন");
+        assert_eq!(processor.preedit(), "");
+
+        type_keys(&mut processor, "bon");
+        processor.reset();
+        assert_eq!(processor.commit().commit, "");
+    }
+
+    #[test]
+    fn test_custom_scheme() {
+        let scheme = Scheme::from_json(
+            r#"{ "name": "tiny", "vowel_signs": { "aa": "া" }, "consonants": { "k": "ক" } }"#,
+        )
+        .unwrap();
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("kaak"), "কাক");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_scheme_file() {
+        let path = std::env::temp_dir().join("obadh-test-scheme.json");
+        std::fs::write(&path, Scheme::avro().to_json().unwrap()).unwrap();
+
+        let mut processor = Processor::from_scheme_file(&path).unwrap();
+        assert_eq!(processor.process_input("amar"), "আমার");
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(Processor::from_scheme_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_patterns_longer_than_five_chars() {
+        let scheme =
+            Scheme::from_json(r#"{ "name": "long", "consonants": { "k": "ক", "kkkkkkk": "খ" } }"#)
+                .unwrap();
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("kkkkkkk"), "খ");
+        assert_eq!(processor.process_input("kkkkkkkk"), "খ্ক");
+    }
+
+    #[test]
+    fn test_case_folding() {
+        let mut processor = Processor::new();
+        assert_eq!(processor.process_input("Kobita"), "কবিতা");
+        assert_eq!(processor.process_input("nT"), "ন্ট");
+    }
+
+    #[test]
+    fn test_case_policy_from_scheme() {
+        let mut scheme = Scheme::avro();
+        scheme.case = CasePolicy {
+            mode: CaseMode::Strict,
+            ..CasePolicy::default()
+        };
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("Kobita"), "Kঅবিতা");
+
+        // A fold exception for `k` covers `kh` too
+        scheme.case.fold.push("k".to_string());
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("KHa"), "খা");
+        assert_eq!(processor.process_input("Ga"), "Gআ");
+
+        // Folding everything but `t` lets `D` fall back to `d`
+        scheme.case = CasePolicy {
+            mode: CaseMode::Fold,
+            strict: vec!["t".to_string()],
+            ..CasePolicy::default()
+        };
+        scheme.consonants.remove("D");
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("Da"), "দা");
+        assert_eq!(processor.process_input("Ta"), "টা");
+    }
+
+    #[test]
+    fn test_phala_and_reph_come_from_scheme() {
+        let mut scheme = Scheme::avro();
+        assert_eq!(Processor::with_scheme(&scheme).process_input("kya"), "ক্যা");
+        assert_eq!(Processor::with_scheme(&scheme).process_input("rrk"), "র্ক");
+        // A repeated phala does not attach again
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("kwww"), "ক্বওও");
+        assert_eq!(processor.process_input("kwyw"), "ক্ব্যও");
+
+        scheme.phalas.remove("y");
+        scheme.reph.clear();
+        scheme.conjuncts = None;
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("kya"), "ক্\u{09DF}া");
+        assert_eq!(processor.process_input("rrk"), "র্র্ক");
+    }
+
+    #[test]
+    fn test_conjunct_whitelist() {
+        let mut processor = Processor::new();
+        assert_eq!(processor.process_input("bd"), "ব্দ");
+        assert_eq!(processor.process_input("utlo"), "উতল");
+        assert_eq!(processor.process_input("hatl"), "হাতল");
+        assert_eq!(processor.process_input("kkh"), "ক্ষ");
+        assert_eq!(processor.process_input("rrk"), "র্ক");
+    }
+
+    #[test]
+    fn test_conjunct_fallback_zwnj() {
+        let mut scheme = Scheme::avro();
+        scheme.conjuncts = Some(ConjunctPolicy {
+            fallback: ConjunctFallback::Zwnj,
+            ..ConjunctPolicy::default()
+        });
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("hatl"), "হাত্\u{200C}ল");
+        assert_eq!(processor.process_input("bd"), "ব্দ");
+    }
+
+    #[test]
+    fn test_conjunct_allow_and_deny() {
+        let mut scheme = Scheme::avro();
+        scheme.conjuncts = Some(ConjunctPolicy {
+            allow: vec!["ত্ল".to_string()],
+            deny: vec!["ব্দ".to_string()],
+            ..ConjunctPolicy::default()
+        });
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("hatl"), "হাত্ল");
+        assert_eq!(processor.process_input("bd"), "বদ");
+
+        scheme.conjuncts = None;
+        let mut processor = Processor::with_scheme(&scheme);
+        assert_eq!(processor.process_input("hatl"), "হাত্ল");
+        assert_eq!(processor.process_input("bd"), "ব্দ");
+    }
+
+    #[test]
+    fn test_khanda_ta() {
+        let mut processor = Processor::new();
+        assert_eq!(processor.process_input("hat`"), "হাৎ");
+        assert_eq!(processor.process_input("ut``sob"), "উৎসব");
+        assert_eq!(processor.process_input("bit` "), "বিৎ ");
+        assert_eq!(processor.process_input("T`"), "ট্\u{200C}");
+        assert_eq!(processor.process_input("ut`sob"), "উত্\u{200C}সব");
+    }
+
+    #[test]
+    fn test_explicit_joiners() {
+        let mut processor = Processor::new();
+        assert_eq!(processor.process_input("k`Sh"), "ক্\u{200C}ষ");
+        assert_eq!(processor.process_input("t`+l"), "ত্\u{200D}ল");
+        assert_eq!(processor.process_input("ko`k"), "ক\u{200C}ক");
+        assert_eq!(processor.process_input("a`i"), "আ\u{200C}ই");
+    }
+
+    #[test]
+    fn test_joiners_incremental() {
+        for input in ["hat`", "ut``sob", "ut`sob", "t`+l", "ko`k"] {
+            let mut processor = Processor::new();
+            let mut committed: String = type_keys(&mut processor, input)
+                .into_iter()
+                .map(|update| update.commit)
+                .collect();
+            committed.push_str(&processor.commit().commit);
+            assert_eq!(committed, processor.process_input(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_numeral_and_punctuation_mode() {
+        let mut processor = Processor::new();
+        assert_eq!(processor.process_input("ami 12 $."), "আমি 12 $.");
+
+        let config = ProcessorConfig {
+            bengali_digits: true,
+            dari: true,
+            taka: true,
+            ..ProcessorConfig::default()
+        };
+        let mut processor = Processor::with_config(&Scheme::avro(), config);
+        assert_eq!(processor.process_input("ami 12 $."), "আমি ১২ ৳।");
+        assert_eq!(processor.process_input("2025 sal"), "২০২৫ সাল");
+        assert_eq!(processor.process_input("\\$5"), "৳৫");
+    }
+
+    #[test]
+    fn test_numeral_mode_incremental() {
+        let mut processor = Processor::new();
+        processor.set_config(ProcessorConfig {
+            bengali_digits: true,
+            dari: true,
+            ..ProcessorConfig::default()
+        });
+
+        let updates = type_keys(&mut processor, "ki 3.");
+        assert_eq!(updates[2].commit, "কি ");
+        assert_eq!(updates[3].preedit, "৩");
+        assert_eq!(updates[4].commit, "৩।");
+    }
+
+    #[test]
+    fn test_normalized_output() {
+        let mut processor = Processor::new();
+        assert_eq!(processor.process_input("poRa"), "প\u{09DC}া");
+
+        processor.set_config(ProcessorConfig {
+            normalization: Some(Normalizer::nfc()),
+            ..ProcessorConfig::default()
+        });
+        assert_eq!(processor.process_input("poRa"), "পড\u{09BC}া");
+
+        let updates = type_keys(&mut processor, "ay ");
+        assert_eq!(updates[1].preedit, "আয\u{09BC}");
+        assert_eq!(updates[2].commit, "আয\u{09BC} ");
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut processor = Processor::new();
+        assert_eq!(
+            processor.candidates("nodi"),
+            vec![Candidate {
+                text: "নদি".to_string(),
+                source: CandidateSource::Rules,
+            }]
+        );
+
+        processor.set_dictionary(Dictionary::parse("নদী 50\nনাদি 10\nদেশ\n").unwrap());
+        let texts = |candidates: Vec<Candidate>| -> Vec<String> {
+            candidates
+                .into_iter()
+                .map(|candidate| candidate.text)
+                .collect()
+        };
+        assert_eq!(texts(processor.candidates("nodi")), ["নদী", "নদি"]);
+        assert_eq!(texts(processor.candidates("desh")), ["দেশ"]);
+        assert_eq!(
+            processor.candidates("desh")[0].source,
+            CandidateSource::Dictionary
+        );
+
+        type_keys(&mut processor, "nod");
+        assert_eq!(texts(processor.composition_candidates()), ["নদ"]);
+    }
+
+    #[test]
+    fn test_autocorrect() {
+        let mut processor = Processor::new();
+        processor.autocorrect_mut().insert("bangladesh", "বাংলাদেশ");
+        processor.autocorrect_mut().insert("ki", "কী");
+
+        assert_eq!(processor.process_input("amar bangladesh."), "আমার বাংলাদেশ.");
+        assert_eq!(processor.process_input("ki khobor"), "কী খবর");
+        assert_eq!(processor.process_input("kire"), "কিরে");
+
+        let updates = type_keys(&mut processor, "ki ");
+        assert_eq!(updates[1].preedit, "কী");
+        assert_eq!(updates[2].commit, "কী ");
+
+        assert_eq!(
+            processor.autocorrect_mut().remove("ki"),
+            Some("কী".to_string())
+        );
+        assert_eq!(processor.process_input("ki"), "কি");
+        assert_eq!(processor.autocorrect().len(), 1);
+    }
+
+    #[test]
+    fn test_autocorrect_incremental_matches_batch() {
+        let mut processor = Processor::new();
+        processor.autocorrect_mut().insert("ki", "কী");
+
+        for input in ["(ki)", "ki.ki", "\"ki\", ki?", "ki-ki"] {
+            let mut committed: String = type_keys(&mut processor, input)
+                .into_iter()
+                .map(|update| update.commit)
+                .collect();
+            committed.push_str(&processor.commit().commit);
+
+            assert_eq!(committed, processor.process_input(input), "{}", input);
+        }
+        assert_eq!(processor.process_input("(ki)"), "(কী)");
+    }
+
+    #[test]
+    fn test_autocorrect_candidate_comes_first() {
+        let mut processor = Processor::new();
+        processor.autocorrect_mut().insert("ki", "কী");
+        processor.set_dictionary(Dictionary::parse("কি\nকী").unwrap());
+
+        let candidates = processor.candidates("ki");
+        assert_eq!(candidates[0].text, "কী");
+        assert_eq!(candidates[0].source, CandidateSource::Autocorrect);
+        assert_eq!(candidates[1].text, "কি");
+        assert_eq!(candidates.len(), 2);
+    }
+
+    #[test]
+    fn test_import_avro_autocorrect() {
+        let mut processor = Processor::new();
+        let count = processor
+            .import_avro_autocorrect(r#"{ "thanks": "dhonnobad", "bd": "বাংলাদেশ" }"#)
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(processor.autocorrect().get("thanks"), Some("ধন্নবাদ"));
+        assert_eq!(processor.process_input("bd, thanks"), "বাংলাদেশ, ধন্নবাদ");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_selection_ranks_first() {
+        let mut processor = Processor::new();
+        processor.set_dictionary(Dictionary::parse("নদী\nনদি").unwrap());
+        assert_eq!(processor.candidates("nodi")[0].text, "নদি");
+
+        type_keys(&mut processor, "nodi");
+        let update = processor.select_candidate("নদী");
+        assert_eq!(update.commit, "নদী");
+        assert!(update.preedit.is_empty());
+
+        let candidates = processor.candidates("nodi");
+        assert_eq!(candidates[0].text, "নদী");
+        assert_eq!(candidates[0].source, CandidateSource::History);
+        assert_eq!(candidates.len(), 2);
+
+        processor.clear_history();
+        assert_eq!(processor.candidates("nodi")[0].text, "নদি");
+    }
+}
@@ -0,0 +1,322 @@
+// crates/core/engine/src/reverse/mod.rs
+
+//! Reverse transliteration from Bengali back to Roman
+//!
+//! The Roman spellings of each Bengali character come from the scheme
+//! tables. A word is spelled one piece at a time, and every choice is checked
+//! against the forward conversion, so that converting the Roman text again
+//! gives back the Bengali text wherever the scheme can express it.
+
+use crate::processor::RuleSet;
+use crate::scheme::Scheme;
+use crate::types::{BengaliChar, ProcessorConfig};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use obadh_bengali::normalize::Normalizer;
+use obadh_bengali::utils::{is_consonant, HASANTA, ZWJ, ZWNJ};
+
+/// Forward conversions tried per piece of a word before giving up on an
+/// exact spelling
+const SEARCH_BUDGET: usize = 64;
+
+/// Converts Bengali text to the Roman input of a scheme
+pub struct Reverser {
+    rules: RuleSet,
+    config: ProcessorConfig,
+    /// Roman spellings of each Bengali character, and of the longer
+    /// sequences that single patterns convert to, best first
+    spellings: BTreeMap<String, Vec<String>>,
+}
+
+/// A run of Bengali text that is spelled as a whole
+struct Piece<'a> {
+    bengali: &'a str,
+    candidates: Vec<String>,
+}
+
+impl Reverser {
+    /// Creates a reverser for the given scheme.
+    ///
+    /// Bengali digits, the dari and the taka sign are spelled as `0`-`9`,
+    /// `.` and `$`.
+    pub fn new(scheme: &Scheme) -> Self {
+        let config = ProcessorConfig {
+            bengali_digits: true,
+            dari: true,
+            taka: true,
+            ..ProcessorConfig::default()
+        };
+
+        let rules = RuleSet::new(scheme);
+        let mut spellings: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (pattern, entry) in scheme.mappings() {
+            let rule_outputs = entry
+                .rules
+                .iter()
+                .filter(|rule| rule.output.len() == 1)
+                .map(|rule| &rule.output[0]);
+            let chars = entry
+                .alternatives
+                .iter()
+                .chain(rule_outputs)
+                .filter_map(BengaliChar::to_char)
+                .chain(entry.phala)
+                .chain(entry.reph);
+            for ch in chars {
+                spellings
+                    .entry(ch.to_string())
+                    .or_default()
+                    .push(pattern.clone());
+            }
+
+            // Patterns whose rules write several characters, such as ZZ
+            let converted = rules.convert(&config, &pattern);
+            if converted.chars().count() > 1 {
+                spellings.entry(converted).or_default().push(pattern);
+            }
+        }
+        for ch in ('0'..='9').chain(['.', '$']) {
+            let converted = config.convert(ch);
+            if converted != ch {
+                spellings
+                    .entry(converted.to_string())
+                    .or_default()
+                    .push(ch.to_string());
+            }
+        }
+        for patterns in spellings.values_mut() {
+            patterns.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+            patterns.dedup();
+        }
+
+        Self {
+            rules,
+            config,
+            spellings,
+        }
+    }
+
+    /// Converts Bengali text to Roman.
+    ///
+    /// Characters the scheme has no spelling for are kept as they are.
+    /// Letters written with a separate nukta are read as the precomposed
+    /// forms that the forward conversion writes.
+    pub fn reverse(&self, text: &str) -> String {
+        let text = Normalizer::precomposed().normalize(text);
+        let mut roman = String::new();
+        let mut rest = text.as_str();
+
+        while !rest.is_empty() {
+            // Whitespace ends every pending state, so words are spelled on
+            // their own
+            let split = rest
+                .find(|ch: char| ch.is_whitespace())
+                .unwrap_or(rest.len());
+            let (word, tail) = rest.split_at(split);
+            roman.push_str(&self.reverse_word(word));
+
+            let space = tail
+                .find(|ch: char| !ch.is_whitespace())
+                .unwrap_or(tail.len());
+            roman.push_str(&tail[..space]);
+            rest = &tail[space..];
+        }
+
+        roman
+    }
+
+    fn reverse_word(&self, word: &str) -> String {
+        let pieces = self.pieces(word);
+        let mut roman = String::new();
+        let mut budget = SEARCH_BUDGET * pieces.len();
+
+        if self.search(word, &pieces, 0, 0, &mut roman, &mut budget) {
+            return roman;
+        }
+
+        // Not expressible exactly; use the preferred spelling of each piece
+        pieces
+            .iter()
+            .map(|piece| piece.candidates[0].as_str())
+            .collect()
+    }
+
+    /// Tries the candidates of each piece in order, keeping a choice only if
+    /// the Roman text so far converts back to the Bengali text so far
+    fn search(
+        &self,
+        word: &str,
+        pieces: &[Piece],
+        index: usize,
+        end: usize,
+        roman: &mut String,
+        budget: &mut usize,
+    ) -> bool {
+        let Some(piece) = pieces.get(index) else {
+            return true;
+        };
+        let end = end + piece.bengali.len();
+
+        for candidate in &piece.candidates {
+            if *budget == 0 {
+                return false;
+            }
+            *budget -= 1;
+
+            let len = roman.len();
+            roman.push_str(candidate);
+            if self.rules.convert(&self.config, roman) == word[..end]
+                && self.search(word, pieces, index + 1, end, roman, budget)
+            {
+                return true;
+            }
+            roman.truncate(len);
+        }
+
+        false
+    }
+
+    /// Splits a word into pieces whose Roman spellings can be chosen one
+    /// after another.
+    ///
+    /// A hasanta is spelled together with what follows it, since the
+    /// forward conversion only writes it once the next consonant arrives.
+    fn pieces<'a>(&self, word: &'a str) -> Vec<Piece<'a>> {
+        let chars: Vec<(usize, char)> = word.char_indices().collect();
+        let mut pieces = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let rest: Vec<char> = chars[i..].iter().map(|&(_, ch)| ch).collect();
+            let (len, key) = match rest.as_slice() {
+                [HASANTA, joiner @ (ZWNJ | ZWJ), ..] => (2, *joiner),
+                [HASANTA, next, ..] if is_consonant(*next) => (2, *next),
+                // র‍্য, which the forward conversion writes for ya-phala after র
+                [ZWJ, HASANTA, next, ..] if is_consonant(*next) => (3, *next),
+                [ch, ..] => (1, *ch),
+                [] => unreachable!(),
+            };
+
+            let start = chars[i].0;
+            let end = chars.get(i + len).map_or(word.len(), |&(index, _)| index);
+            let bengali = &word[start..end];
+            let mut candidates = self
+                .spellings
+                .get(key.encode_utf8(&mut [0; 4]) as &str)
+                .cloned()
+                .unwrap_or_else(|| vec![key.to_string()]);
+            if bengali.chars().count() > 1 {
+                candidates.extend(self.spellings.get(bengali).into_iter().flatten().cloned());
+            }
+
+            // A consonant that keeps its inherent vowel is spelled with `o`
+            // inside a word, which also stops it from joining what follows
+            let next = rest.get(len).copied();
+            if is_consonant(key) && !next.is_some_and(takes_part_in_cluster) {
+                let with_vowel: Vec<String> = candidates.iter().map(|c| format!("{c}o")).collect();
+                if next.is_some_and(|ch| ('\u{0980}'..='\u{09E5}').contains(&ch)) {
+                    candidates.splice(0..0, with_vowel);
+                } else {
+                    candidates.extend(with_vowel);
+                }
+            }
+
+            pieces.push(Piece {
+                bengali,
+                candidates,
+            });
+            i += len;
+        }
+
+        pieces
+    }
+}
+
+/// Checks whether `ch` continues the consonant before it, so that the
+/// consonant has no inherent vowel
+fn takes_part_in_cluster(ch: char) -> bool {
+    matches!(ch, HASANTA | ZWJ | '\u{09BE}'..='\u{09CC}' | '\u{09D7}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::Processor;
+
+    #[test]
+    fn test_reverse_simple_words() {
+        let reverser = Reverser::new(&Scheme::avro());
+        assert_eq!(reverser.reverse("আমি"), "ami");
+        assert_eq!(reverser.reverse("আমি তুমি"), "ami tumi");
+        assert_eq!(reverser.reverse("কবিতা"), "kobita");
+        assert_eq!(reverser.reverse("বাংলা"), "bangla");
+    }
+
+    #[test]
+    fn test_reverse_conjuncts_and_nukta() {
+        let reverser = Reverser::new(&Scheme::avro());
+        assert_eq!(reverser.reverse("বন্ধু"), "bondhu");
+        assert_eq!(reverser.reverse("কর্ম"), "korm");
+        assert_eq!(reverser.reverse("পড়া"), "poRa");
+        assert_eq!(reverser.reverse("কয়"), "koy");
+    }
+
+    #[test]
+    fn test_reverse_separate_nukta() {
+        let reverser = Reverser::new(&Scheme::avro());
+        assert_eq!(reverser.reverse("\u{09AA}\u{09A1}\u{09BC}\u{09BE}"), "poRa");
+        assert_eq!(reverser.reverse("\u{09AF}\u{09BC}"), "y");
+        assert_eq!(
+            reverser.reverse("\u{0986}\u{09B7}\u{09BE}\u{09A2}\u{09BC}"),
+            "aShaRh"
+        );
+    }
+
+    #[test]
+    fn test_reverse_digits_and_punctuation() {
+        let reverser = Reverser::new(&Scheme::avro());
+        assert_eq!(reverser.reverse("১২৩।"), "123.");
+        assert_eq!(reverser.reverse("৳৫"), "$5");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let scheme = Scheme::avro();
+        let reverser = Reverser::new(&scheme);
+        let processor = Processor::with_config(
+            &scheme,
+            ProcessorConfig {
+                bengali_digits: true,
+                dari: true,
+                taka: true,
+                ..ProcessorConfig::default()
+            },
+        );
+
+        let words = [
+            "বাংলাদেশ",
+            "ব্যাকরণ",
+            "কর্তব্য",
+            "উৎসব",
+            "হঠাৎ",
+            "র‍্যাব",
+            "ক্ষমা",
+            "দুঃখ",
+            "চাঁদ",
+            "অক্টোবর",
+            "ঐতিহ্য",
+            "স্বপ্ন",
+            "কই",
+            "হাত্\u{200C}ল",
+            "আষাঢ়",
+            "পড়ুয়া",
+        ];
+        for word in words {
+            let roman = reverser.reverse(word);
+            assert_eq!(processor.convert(&roman), word, "{}", roman);
+        }
+    }
+}
@@ -0,0 +1,838 @@
+// crates/core/engine/src/scheme/mod.rs
+
+//! Transliteration schemes loaded from data files
+//!
+//! A scheme is a JSON document with one table per kind of Bengali character.
+//! Each table maps a Roman pattern to the character it produces:
+//!
+//! ```json
+//! {
+//!   "name": "avro",
+//!   "vowels": { "a": "আ" },
+//!   "vowel_signs": { "a": "া" },
+//!   "consonants": { "k": "ক", "kh": "খ" },
+//!   "specials": { "\\^": "ঁ" },
+//!   "symbols": { "\\\\": "\\" }
+//! }
+//! ```
+//!
+//! A pattern may appear in several tables; the processor then picks the
+//! variant that fits the context, e.g. the vowel sign after a consonant.
+//!
+//! Patterns whose output depends on the neighbouring Roman input get
+//! `rules`, modelled on the Avro Phonetic rule set. The first rule whose
+//! conditions all hold produces its `output`, an explicit sequence of
+//! characters fed to the processor one after another. When no rule applies
+//! the tables are used, and a pattern without table entries does not match
+//! at all, so a shorter pattern is tried instead:
+//!
+//! ```json
+//! "rules": {
+//!   "a": [
+//!     {
+//!       "when": [{ "type": "prefix", "scope": "vowel" }],
+//!       "output": [{ "Consonant": "য়" }, { "VowelSign": "া" }]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Phala and reph forms have their own tables. A `phalas` pattern typed
+//! right after a consonant attaches to it with a hasanta (ya-phala after র
+//! gets a ZWJ, as in র‍্য); anywhere else the pattern falls back to its
+//! other tables. A `reph` pattern followed by a Roman consonant becomes a
+//! র that joins the next consonant:
+//!
+//! ```json
+//! "phalas": { "y": "য", "r": "র", "w": "ব", "m": "ম" },
+//! "reph": { "rr": "র" }
+//! ```
+//!
+//! Without a `conjuncts` section any two consonants typed in a row are
+//! joined with a hasanta. With it, only the pairs it allows are joined and
+//! the others fall back to the inherent vowel (ত ল) or a hasanta with ZWNJ
+//! (ত্‌ল). Phalas and reph are always joined:
+//!
+//! ```json
+//! "conjuncts": {
+//!   "standard": true,
+//!   "allow": ["ত্ল"],
+//!   "deny": ["ক্ন"],
+//!   "fallback": "inherent"
+//! }
+//! ```
+//!
+//! Input whose case differs from every pattern is matched case-folded, so
+//! `K` finds `k`. `case` switches that off with `"mode": "strict"`, and lists
+//! patterns that are exceptions to the mode. An exception also covers the
+//! longer patterns that start with it, and the longest one applies:
+//!
+//! ```json
+//! "case": { "mode": "fold", "strict": ["t", "d"], "fold": ["th"] }
+//! ```
+//!
+//! Dictionary suggestions try every spelling the tables give a pattern,
+//! ignoring case, so `s` finds স and শ. `suggestions` adds spellings that
+//! only the dictionary search should consider:
+//!
+//! ```json
+//! "suggestions": { "s": ["ষ"], "j": ["য"] }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::types::BengaliChar;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use obadh_bengali::conjuncts::{is_standard_conjunct, STANDARD_CONJUNCTS};
+use obadh_bengali::utils::{is_consonant, HASANTA, KHANDA_TA, ZWJ, ZWNJ};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+// The Avro Phonetic scheme shipped with the engine, written out by build.rs
+include!(concat!(env!("OUT_DIR"), "/avro.rs"));
+
+/// Longest exact prefix a rule condition may ask for, in characters
+pub(crate) const MAX_PREFIX_LEN: usize = 8;
+
+/// Roman patterns mapped to the Bengali character they produce
+pub type PatternTable = BTreeMap<String, char>;
+
+/// Which side of the matched pattern a condition looks at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Prefix,
+    Suffix,
+}
+
+/// What a condition expects to find next to the pattern
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// A Roman vowel letter: a, e, i, o or u in either case
+    Vowel,
+    /// Any other ASCII letter
+    Consonant,
+    /// Anything that is not a letter, including the start or end of input
+    Punctuation,
+    /// The literal text given in `value`
+    Exact,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Condition {
+    #[serde(rename = "type")]
+    pub side: Side,
+    pub scope: Scope,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub value: String,
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub negate: bool,
+}
+
+impl Condition {
+    /// Checks the condition against the Roman input around the pattern
+    pub(crate) fn matches(&self, prefix: &str, suffix: &str) -> bool {
+        let neighbour = match self.side {
+            Side::Prefix => prefix.chars().next_back(),
+            Side::Suffix => suffix.chars().next(),
+        };
+        let holds = match self.scope {
+            Scope::Vowel => neighbour.is_some_and(is_roman_vowel),
+            Scope::Consonant => {
+                neighbour.is_some_and(|ch| ch.is_ascii_alphabetic() && !is_roman_vowel(ch))
+            }
+            Scope::Punctuation => !neighbour.is_some_and(|ch| ch.is_ascii_alphabetic()),
+            Scope::Exact => match self.side {
+                Side::Prefix => prefix.ends_with(&self.value),
+                Side::Suffix => suffix.starts_with(&self.value),
+            },
+        };
+        holds != self.negate
+    }
+
+    /// Number of characters after the pattern needed to decide the condition
+    fn lookahead(&self) -> usize {
+        match (self.side, self.scope) {
+            (Side::Prefix, _) => 0,
+            (Side::Suffix, Scope::Exact) => self.value.chars().count(),
+            (Side::Suffix, _) => 1,
+        }
+    }
+}
+
+/// What to do with a consonant pair that may not form a conjunct
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConjunctFallback {
+    /// Leave the inherent vowel on the first consonant
+    #[default]
+    Inherent,
+    /// Write a hasanta followed by ZWNJ, so no ligature is formed
+    Zwnj,
+}
+
+/// Whether input may match a pattern of different case
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseMode {
+    /// Patterns match only as written
+    Strict,
+    /// Patterns also match input that differs in case, e.g. `K` for `k`
+    #[default]
+    Fold,
+}
+
+/// Case matching of a scheme, with per-pattern exceptions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CasePolicy {
+    #[serde(default)]
+    pub mode: CaseMode,
+    /// Patterns matched only as written, together with the longer patterns
+    /// starting with them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strict: Vec<String>,
+    /// Patterns matched case-folded, together with the longer patterns
+    /// starting with them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fold: Vec<String>,
+}
+
+impl CasePolicy {
+    /// Checks whether `pattern` may match input that differs in case
+    pub fn folds(&self, pattern: &str) -> bool {
+        let strict = self.strict.iter().map(|prefix| (prefix, CaseMode::Strict));
+        let fold = self.fold.iter().map(|prefix| (prefix, CaseMode::Fold));
+        let mode = strict
+            .chain(fold)
+            .filter(|(prefix, _)| pattern.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.mode, |(_, mode)| mode);
+        mode == CaseMode::Fold
+    }
+
+    /// Checks whether any pattern may match case-folded
+    pub(crate) fn folds_any(&self) -> bool {
+        self.mode == CaseMode::Fold || !self.fold.is_empty()
+    }
+}
+
+/// Which consonant pairs may be joined into conjuncts
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConjunctPolicy {
+    /// Start from the standard list in `obadh-bengali`
+    #[serde(default = "default_true")]
+    pub standard: bool,
+    /// Extra pairs, written as the conjunct (ত্ল) or the two consonants (তল)
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Pairs removed from the list, in the same format as `allow`
+    #[serde(default)]
+    pub deny: Vec<String>,
+    #[serde(default)]
+    pub fallback: ConjunctFallback,
+}
+
+impl Default for ConjunctPolicy {
+    fn default() -> Self {
+        Self {
+            standard: true,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            fallback: ConjunctFallback::default(),
+        }
+    }
+}
+
+impl ConjunctPolicy {
+    /// Builds the set of allowed pairs
+    pub(crate) fn compile(&self) -> ConjunctSet {
+        let mut pairs: BTreeSet<(char, char)> = BTreeSet::new();
+        if self.standard {
+            pairs.extend(STANDARD_CONJUNCTS.iter().copied());
+        }
+        pairs.extend(self.allow.iter().filter_map(|pair| parse_pair(pair)));
+
+        ConjunctSet {
+            pairs,
+            reph: self.standard,
+            denied: self
+                .deny
+                .iter()
+                .filter_map(|pair| parse_pair(pair))
+                .collect(),
+            fallback: self.fallback,
+        }
+    }
+}
+
+/// Compiled form of a [`ConjunctPolicy`]
+#[derive(Clone, Debug)]
+pub(crate) struct ConjunctSet {
+    pairs: BTreeSet<(char, char)>,
+    /// Whether র joins any consonant, as the standard list implies
+    reph: bool,
+    denied: BTreeSet<(char, char)>,
+    pub(crate) fallback: ConjunctFallback,
+}
+
+impl ConjunctSet {
+    pub(crate) fn allows(&self, first: char, second: char) -> bool {
+        if self.denied.contains(&(first, second)) {
+            return false;
+        }
+        self.pairs.contains(&(first, second)) || (self.reph && is_standard_conjunct(first, second))
+    }
+}
+
+/// Reads a pair such as "ত্ল" or "তল" into its two consonants
+fn parse_pair(pair: &str) -> Option<(char, char)> {
+    let mut consonants = pair.chars().filter(|&ch| ch != HASANTA && ch != ZWJ);
+    match (consonants.next(), consonants.next(), consonants.next()) {
+        (Some(first), Some(second), None) if is_consonant(first) && is_consonant(second) => {
+            Some((first, second))
+        }
+        _ => None,
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    pub when: Vec<Condition>,
+    pub output: Vec<BengaliChar>,
+}
+
+impl Rule {
+    pub(crate) fn matches(&self, prefix: &str, suffix: &str) -> bool {
+        self.when
+            .iter()
+            .all(|condition| condition.matches(prefix, suffix))
+    }
+}
+
+/// What the processor does with a matched pattern
+pub(crate) enum Resolution<'a> {
+    /// Pick one of these by context
+    Alternatives(&'a [BengaliChar]),
+    /// Feed all of these in order
+    Sequence(&'a [BengaliChar]),
+    /// Attach this consonant to the pending one
+    Phala(char),
+    /// Start a reph that joins the next consonant
+    Reph(char),
+}
+
+/// A compiled scheme entry
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Pattern {
+    pub(crate) alternatives: Vec<BengaliChar>,
+    pub(crate) rules: Vec<Rule>,
+    pub(crate) phala: Option<char>,
+    pub(crate) reph: Option<char>,
+    /// Characters of following input the rules need to see
+    pub(crate) lookahead: usize,
+    /// Whether input that differs in case may match
+    pub(crate) folds: bool,
+}
+
+impl Pattern {
+    /// Chooses the output for the given Roman context, if the pattern applies.
+    ///
+    /// `after_consonant` tells whether a consonant is waiting that a phala
+    /// could attach to.
+    pub(crate) fn resolve(
+        &self,
+        prefix: &str,
+        suffix: &str,
+        after_consonant: bool,
+    ) -> Option<Resolution<'_>> {
+        if let Some(rule) = self.rules.iter().find(|rule| rule.matches(prefix, suffix)) {
+            return Some(Resolution::Sequence(&rule.output));
+        }
+        if let Some(ch) = self.reph {
+            let before_consonant = suffix
+                .chars()
+                .next()
+                .is_some_and(|next| next.is_ascii_alphabetic() && !is_roman_vowel(next));
+            if before_consonant {
+                return Some(Resolution::Reph(ch));
+            }
+        }
+        if let Some(ch) = self.phala.filter(|_| after_consonant) {
+            return Some(Resolution::Phala(ch));
+        }
+        if self.alternatives.is_empty() {
+            None
+        } else {
+            Some(Resolution::Alternatives(&self.alternatives))
+        }
+    }
+}
+
+fn is_roman_vowel(ch: char) -> bool {
+    matches!(ch.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scheme {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub vowels: PatternTable,
+    #[serde(default)]
+    pub vowel_signs: PatternTable,
+    #[serde(default)]
+    pub consonants: PatternTable,
+    #[serde(default)]
+    pub specials: PatternTable,
+    #[serde(default)]
+    pub symbols: PatternTable,
+    #[serde(default)]
+    pub phalas: PatternTable,
+    #[serde(default)]
+    pub reph: PatternTable,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conjuncts: Option<ConjunctPolicy>,
+    #[serde(default)]
+    pub case: CasePolicy,
+    #[serde(default)]
+    pub rules: BTreeMap<String, Vec<Rule>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub suggestions: BTreeMap<String, Vec<String>>,
+}
+
+impl Scheme {
+    /// Returns the built-in Avro Phonetic scheme
+    pub fn avro() -> Self {
+        Self::from_static(&AVRO)
+    }
+
+    /// Parses and validates a scheme from its JSON representation
+    pub fn from_json(json: &str) -> Result<Self> {
+        let scheme: Scheme = serde_json::from_str(json)?;
+        scheme.validate()?;
+        Ok(scheme)
+    }
+
+    /// Reads a scheme from a JSON file
+    #[cfg(feature = "std")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// Serializes the scheme back to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(Error::InvalidScheme("scheme name is empty".to_string()));
+        }
+
+        let tables = [
+            ("vowels", &self.vowels),
+            ("vowel_signs", &self.vowel_signs),
+            ("consonants", &self.consonants),
+            ("specials", &self.specials),
+            ("symbols", &self.symbols),
+            ("phalas", &self.phalas),
+            ("reph", &self.reph),
+        ];
+        for (table, entries) in tables {
+            if entries.keys().any(|pattern| pattern.is_empty()) {
+                return Err(Error::InvalidScheme(format!(
+                    "empty pattern in `{}` of scheme `{}`",
+                    table, self.name
+                )));
+            }
+        }
+
+        if let Some(conjuncts) = &self.conjuncts {
+            let pairs = conjuncts.allow.iter().chain(&conjuncts.deny);
+            if let Some(pair) = pairs.into_iter().find(|pair| parse_pair(pair).is_none()) {
+                return Err(Error::InvalidScheme(format!(
+                    "`{}` is not a pair of consonants",
+                    pair
+                )));
+            }
+        }
+
+        let overrides = self.case.strict.iter().chain(&self.case.fold);
+        if overrides.into_iter().any(|pattern| pattern.is_empty()) {
+            return Err(Error::InvalidScheme(format!(
+                "empty pattern in `case` of scheme `{}`",
+                self.name
+            )));
+        }
+
+        if self.suggestions.keys().any(|pattern| pattern.is_empty()) {
+            return Err(Error::InvalidScheme(format!(
+                "empty pattern in `suggestions` of scheme `{}`",
+                self.name
+            )));
+        }
+
+        for (pattern, rules) in &self.rules {
+            if pattern.is_empty() {
+                return Err(Error::InvalidScheme(format!(
+                    "empty pattern in `rules` of scheme `{}`",
+                    self.name
+                )));
+            }
+            for rule in rules {
+                if rule.output.is_empty() {
+                    return Err(Error::InvalidScheme(format!(
+                        "rule for `{}` has no output",
+                        pattern
+                    )));
+                }
+                for condition in &rule.when {
+                    let exact = condition.scope == Scope::Exact;
+                    if exact && condition.value.is_empty() {
+                        return Err(Error::InvalidScheme(format!(
+                            "exact condition for `{}` has no value",
+                            pattern
+                        )));
+                    }
+                    let too_long = condition.value.chars().count() > MAX_PREFIX_LEN;
+                    if exact && condition.side == Side::Prefix && too_long {
+                        return Err(Error::InvalidScheme(format!(
+                            "exact prefix for `{}` is longer than {} characters",
+                            pattern, MAX_PREFIX_LEN
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the pattern table used by the processor
+    pub(crate) fn mappings(&self) -> BTreeMap<String, Pattern> {
+        let mut mappings: BTreeMap<String, Pattern> = BTreeMap::new();
+
+        let tables = [
+            (&self.vowels, BengaliChar::Vowel as fn(char) -> BengaliChar),
+            (&self.vowel_signs, BengaliChar::VowelSign),
+            (&self.consonants, BengaliChar::Consonant),
+            (&self.specials, BengaliChar::Special),
+            (&self.symbols, BengaliChar::Symbol),
+        ];
+        for (entries, variant) in tables {
+            for (pattern, &ch) in entries {
+                mappings
+                    .entry(pattern.clone())
+                    .or_default()
+                    .alternatives
+                    .push(match ch {
+                        ZWNJ => BengaliChar::Zwnj,
+                        ZWJ => BengaliChar::Zwj,
+                        KHANDA_TA => BengaliChar::KhandaTa,
+                        _ => variant(ch),
+                    });
+            }
+        }
+
+        for (pattern, &ch) in &self.phalas {
+            mappings.entry(pattern.clone()).or_default().phala = Some(ch);
+        }
+        for (pattern, &ch) in &self.reph {
+            let entry = mappings.entry(pattern.clone()).or_default();
+            entry.reph = Some(ch);
+            entry.lookahead = 1;
+        }
+
+        for (pattern, rules) in &self.rules {
+            let entry = mappings.entry(pattern.clone()).or_default();
+            entry.lookahead = rules
+                .iter()
+                .flat_map(|rule| &rule.when)
+                .map(Condition::lookahead)
+                .fold(entry.lookahead, usize::max);
+            entry.rules = rules.clone();
+        }
+
+        for (pattern, entry) in &mut mappings {
+            entry.folds = self.case.folds(pattern);
+        }
+
+        mappings
+    }
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Self::avro()
+    }
+}
+
+/// A scheme as static data. `build.rs` writes the built-in schemes in this
+/// form, so they load without parsing JSON.
+struct StaticScheme {
+    name: &'static str,
+    description: &'static str,
+    vowels: &'static [(&'static str, char)],
+    vowel_signs: &'static [(&'static str, char)],
+    consonants: &'static [(&'static str, char)],
+    specials: &'static [(&'static str, char)],
+    symbols: &'static [(&'static str, char)],
+    phalas: &'static [(&'static str, char)],
+    reph: &'static [(&'static str, char)],
+    conjuncts: Option<StaticConjuncts>,
+    case: StaticCase,
+    rules: &'static [(&'static str, &'static [StaticRule])],
+    suggestions: &'static [(&'static str, &'static [&'static str])],
+}
+
+struct StaticConjuncts {
+    standard: bool,
+    allow: &'static [&'static str],
+    deny: &'static [&'static str],
+    fallback: ConjunctFallback,
+}
+
+struct StaticCase {
+    mode: CaseMode,
+    strict: &'static [&'static str],
+    fold: &'static [&'static str],
+}
+
+struct StaticRule {
+    when: &'static [StaticCondition],
+    output: &'static [BengaliChar],
+}
+
+struct StaticCondition {
+    side: Side,
+    scope: Scope,
+    value: &'static str,
+    negate: bool,
+}
+
+impl Scheme {
+    fn from_static(scheme: &StaticScheme) -> Self {
+        let table = |entries: &[(&str, char)]| -> PatternTable {
+            entries
+                .iter()
+                .map(|&(pattern, ch)| (pattern.to_string(), ch))
+                .collect()
+        };
+        let strings =
+            |items: &[&str]| -> Vec<String> { items.iter().map(|item| item.to_string()).collect() };
+        let rule = |rule: &StaticRule| Rule {
+            when: rule
+                .when
+                .iter()
+                .map(|condition| Condition {
+                    side: condition.side,
+                    scope: condition.scope,
+                    value: condition.value.to_string(),
+                    negate: condition.negate,
+                })
+                .collect(),
+            output: rule.output.to_vec(),
+        };
+
+        Self {
+            name: scheme.name.to_string(),
+            description: scheme.description.to_string(),
+            vowels: table(scheme.vowels),
+            vowel_signs: table(scheme.vowel_signs),
+            consonants: table(scheme.consonants),
+            specials: table(scheme.specials),
+            symbols: table(scheme.symbols),
+            phalas: table(scheme.phalas),
+            reph: table(scheme.reph),
+            conjuncts: scheme.conjuncts.as_ref().map(|conjuncts| ConjunctPolicy {
+                standard: conjuncts.standard,
+                allow: strings(conjuncts.allow),
+                deny: strings(conjuncts.deny),
+                fallback: conjuncts.fallback,
+            }),
+            case: CasePolicy {
+                mode: scheme.case.mode,
+                strict: strings(scheme.case.strict),
+                fold: strings(scheme.case.fold),
+            },
+            rules: scheme
+                .rules
+                .iter()
+                .map(|&(pattern, rules)| (pattern.to_string(), rules.iter().map(rule).collect()))
+                .collect(),
+            suggestions: scheme
+                .suggestions
+                .iter()
+                .map(|&(pattern, spellings)| (pattern.to_string(), strings(spellings)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_avro_matches_json() {
+        let json = include_str!("../../schemes/avro.json");
+        assert_eq!(Scheme::avro(), Scheme::from_json(json).unwrap());
+    }
+
+    #[test]
+    fn test_avro_scheme_loads() {
+        let scheme = Scheme::avro();
+        assert_eq!(scheme.name, "avro");
+        assert_eq!(scheme.consonants.get("kh"), Some(&'খ'));
+        assert_eq!(scheme.specials.get("\\^"), Some(&'ঁ'));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let scheme = Scheme::avro();
+        let json = scheme.to_json().unwrap();
+        assert_eq!(Scheme::from_json(&json).unwrap(), scheme);
+    }
+
+    #[test]
+    fn test_shared_pattern_keeps_both_variants() {
+        let mappings = Scheme::avro().mappings();
+        let a = &mappings["a"].alternatives;
+        assert!(matches!(a[0], BengaliChar::Vowel('আ')));
+        assert!(matches!(a[1], BengaliChar::VowelSign('া')));
+    }
+
+    #[test]
+    fn test_case_policy() {
+        let policy = CasePolicy {
+            mode: CaseMode::Fold,
+            strict: vec!["t".to_string()],
+            fold: vec!["th".to_string()],
+        };
+        assert!(policy.folds("k"));
+        assert!(!policy.folds("t"));
+        assert!(!policy.folds("t`"));
+        assert!(policy.folds("th"));
+        assert!(policy.folds("thh"));
+
+        let policy = CasePolicy {
+            mode: CaseMode::Strict,
+            fold: vec!["k".to_string()],
+            ..CasePolicy::default()
+        };
+        assert!(policy.folds("kh"));
+        assert!(!policy.folds("g"));
+        assert!(policy.folds_any());
+        assert!(!CasePolicy {
+            mode: CaseMode::Strict,
+            ..CasePolicy::default()
+        }
+        .folds_any());
+    }
+
+    #[test]
+    fn test_rejects_invalid_scheme() {
+        assert!(matches!(
+            Scheme::from_json(r#"{ "name": "" }"#),
+            Err(Error::InvalidScheme(_))
+        ));
+        assert!(matches!(
+            Scheme::from_json(r#"{ "name": "x", "consonants": { "": "ক" } }"#),
+            Err(Error::InvalidScheme(_))
+        ));
+        assert!(matches!(
+            Scheme::from_json(r#"{ "name": "x", "consonants": { "k": "কখ" } }"#),
+            Err(Error::SchemeError(_))
+        ));
+        assert!(matches!(
+            Scheme::from_json(r#"{ "name": "x", "case": { "strict": [""] } }"#),
+            Err(Error::InvalidScheme(_))
+        ));
+    }
+
+    #[test]
+    fn test_rule_conditions() {
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "when": [
+                    { "type": "prefix", "scope": "consonant" },
+                    { "type": "suffix", "scope": "exact", "value": "a", "negate": true }
+                ],
+                "output": [{ "Consonant": "য" }]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(rule.matches("k", "u"));
+        assert!(rule.matches("k", ""));
+        assert!(!rule.matches("k", "a"));
+        assert!(!rule.matches("a", "u"));
+        assert!(!rule.matches("", "u"));
+    }
+
+    #[test]
+    fn test_punctuation_scope_covers_boundaries() {
+        let condition = Condition {
+            side: Side::Prefix,
+            scope: Scope::Punctuation,
+            value: String::new(),
+            negate: false,
+        };
+        assert!(condition.matches("", ""));
+        assert!(condition.matches("ami ", ""));
+        assert!(!condition.matches("ami", ""));
+    }
+
+    #[test]
+    fn test_rejects_invalid_rules() {
+        assert!(matches!(
+            Scheme::from_json(r#"{ "name": "x", "rules": { "y": [{ "output": [] }] } }"#),
+            Err(Error::InvalidScheme(_))
+        ));
+        assert!(matches!(
+            Scheme::from_json(
+                r#"{ "name": "x", "rules": { "y": [{
+                    "when": [{ "type": "prefix", "scope": "exact" }],
+                    "output": [{ "Consonant": "য" }]
+                }] } }"#
+            ),
+            Err(Error::InvalidScheme(_))
+        ));
+    }
+
+    #[test]
+    fn test_conjunct_policy() {
+        let policy = ConjunctPolicy {
+            allow: vec!["ত্ল".to_string()],
+            deny: vec!["কষ".to_string(), "রক".to_string()],
+            ..ConjunctPolicy::default()
+        };
+        let set = policy.compile();
+
+        assert!(set.allows('ত', 'ল'));
+        assert!(set.allows('ন', 'ত'));
+        assert!(!set.allows('ক', 'ষ'));
+        assert!(set.allows('র', 'ম'));
+        assert!(!set.allows('র', 'ক'));
+        assert!(!set.allows('খ', 'ক'));
+    }
+
+    #[test]
+    fn test_rejects_invalid_conjunct_pair() {
+        assert!(matches!(
+            Scheme::from_json(r#"{ "name": "x", "conjuncts": { "allow": ["কা"] } }"#),
+            Err(Error::InvalidScheme(_))
+        ));
+    }
+}
@@ -0,0 +1,446 @@
+// crates/core/engine/src/session/mod.rs
+
+//! Per-context typing state over a shared [`RuleSet`]
+//!
+//! An input method serving many text fields builds the rule set once and
+//! opens a [`Session`] per field:
+//!
+//! ```
+//! use obadh_engine::{RuleSet, Scheme, Session};
+//! use std::sync::Arc;
+//!
+//! let rules = Arc::new(RuleSet::new(&Scheme::avro()));
+//! let mut first = Session::new(Arc::clone(&rules));
+//! let mut second = Session::new(rules);
+//!
+//! first.push_char('k');
+//! second.push_char('a');
+//! assert_eq!(first.preedit(), "ক");
+//! assert_eq!(second.preedit(), "আ");
+//! ```
+
+use crate::error::Result;
+use crate::layout::{KeyPress, Layout, LayoutProcessor};
+use crate::processor::{is_punctuation, normalized, RuleSet};
+#[cfg(feature = "std")]
+use crate::stream;
+use crate::trace::Trace;
+use crate::types::{
+    BackspaceMode, Candidate, CommittedWord, CompositionUpdate, ProcessingContext, ProcessorConfig,
+};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{BufRead, Write};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The word being typed in one input context.
+///
+/// A session holds an `Arc` of its rules and never copies them. Its own
+/// memory is `size_of::<Session>()`, a few hundred bytes, plus the Roman
+/// input and output of the word being typed, which are freed on commit.
+#[derive(Clone, Debug)]
+pub struct Session {
+    rules: Arc<RuleSet>,
+    config: ProcessorConfig,
+    context: ProcessingContext,
+    composition: Composition,
+    /// Word committed by the last keystroke, which backspace can revert
+    last_commit: Option<CommittedWord>,
+    /// Fixed layout that keystrokes are typed on instead of the rules
+    layout: Option<Box<LayoutProcessor>>,
+}
+
+/// The word currently being typed in incremental mode
+#[derive(Clone, Debug, Default)]
+struct Composition {
+    /// Roman input of the word, kept so that backspace can re-convert it
+    roman: String,
+    /// Bengali output that no later keystroke of the word can change
+    output: String,
+}
+
+impl Session {
+    /// Opens a session with the default configuration
+    pub fn new(rules: Arc<RuleSet>) -> Self {
+        Self::with_config(rules, ProcessorConfig::default())
+    }
+
+    pub fn with_config(rules: Arc<RuleSet>, config: ProcessorConfig) -> Self {
+        Self {
+            rules,
+            config,
+            context: ProcessingContext::default(),
+            composition: Composition::default(),
+            last_commit: None,
+            layout: None,
+        }
+    }
+
+    pub fn rules(&self) -> &Arc<RuleSet> {
+        &self.rules
+    }
+
+    /// Gives mutable access to the rules, copying them first if other
+    /// sessions share them
+    pub(crate) fn rules_mut(&mut self) -> &mut RuleSet {
+        Arc::make_mut(&mut self.rules)
+    }
+
+    pub fn config(&self) -> &ProcessorConfig {
+        &self.config
+    }
+
+    /// Changes the configuration; it applies from the next keystroke on
+    pub fn set_config(&mut self, config: ProcessorConfig) {
+        self.config = config;
+    }
+
+    /// Switches between phonetic input and a fixed keyboard layout.
+    ///
+    /// With a layout, keystrokes name key positions: [`Session::push_key`]
+    /// takes them with their modifiers, and [`Session::push_char`] takes the
+    /// character the key types on a US keyboard. The current composition is
+    /// discarded.
+    pub fn set_layout(&mut self, layout: Option<Layout>) {
+        self.reset();
+        self.layout = layout.map(|layout| Box::new(LayoutProcessor::new(layout)));
+    }
+
+    /// Returns the fixed layout in use, if any
+    pub fn layout(&self) -> Option<&Layout> {
+        self.layout.as_deref().map(LayoutProcessor::layout)
+    }
+
+    /// Returns the Roman input of the word being composed
+    pub fn roman(&self) -> &str {
+        &self.composition.roman
+    }
+
+    /// Converts a complete Roman string without touching the composition;
+    /// see [`RuleSet::process_input`]. With a layout, the input is read as
+    /// text typed on a US keyboard.
+    pub fn process_input(&self, input: &str) -> String {
+        match self.layout.as_deref() {
+            Some(layout) => layout.process_input(input),
+            None => self.rules.process_input(&self.config, input),
+        }
+    }
+
+    /// Converts a complete Roman string, failing if any of it did not
+    /// convert cleanly; see [`RuleSet::process_checked`]
+    pub fn process_checked(&self, input: &str) -> Result<String> {
+        self.rules.process_checked(&self.config, input)
+    }
+
+    /// Converts a complete Roman string, recording how; see
+    /// [`RuleSet::trace`]
+    pub fn trace(&self, input: &str) -> Trace {
+        self.rules.trace(&self.config, input)
+    }
+
+    /// Converts Roman text read from `reader` and writes it to `writer`
+    /// without touching the composition; see [`stream::convert_stream`]
+    #[cfg(feature = "std")]
+    pub fn convert_stream(&self, reader: impl BufRead, writer: impl Write) -> Result<()> {
+        stream::convert_stream(&self.rules, &self.config, reader, writer)
+    }
+
+    /// Returns the Bengali words that `roman` may stand for, best first
+    pub fn candidates(&self, roman: &str) -> Vec<Candidate> {
+        self.rules.candidates(&self.config, roman)
+    }
+
+    /// Returns the candidates for the word being composed
+    pub fn composition_candidates(&self) -> Vec<Candidate> {
+        self.candidates(&self.composition.roman)
+    }
+
+    /// Adds one keystroke to the current composition.
+    ///
+    /// Only the new keystroke is converted; the word typed so far is not
+    /// re-run. The word is committed once a space or punctuation mark ends it.
+    pub fn push_char(&mut self, ch: char) -> CompositionUpdate {
+        self.last_commit = None;
+        if let Some(layout) = self.layout.as_deref_mut() {
+            return layout.push_key(KeyPress::from_us_char(ch));
+        }
+        self.rules.feed(
+            &self.config,
+            &mut self.context,
+            ch,
+            &mut self.composition.output,
+        );
+        self.composition.roman.push(ch);
+
+        let ends_word = is_punctuation(ch)
+            && self.composition.output.ends_with(self.config.convert(ch))
+            && self.context.pending_input.is_empty()
+            && self.context.pending_consonant.is_none();
+        if ends_word {
+            return self.commit();
+        }
+
+        self.composition_update(String::new())
+    }
+
+    /// Adds one key press to the current composition.
+    ///
+    /// Without a layout the key types its US keyboard character, as with
+    /// [`Session::push_char`].
+    pub fn push_key(&mut self, key: KeyPress) -> CompositionUpdate {
+        match self.layout.as_deref_mut() {
+            Some(layout) => {
+                self.last_commit = None;
+                layout.push_key(key)
+            }
+            None => self.push_char(key.us_char()),
+        }
+    }
+
+    /// Removes the last Roman character of the current composition, or the
+    /// last grapheme cluster of its preedit under [`BackspaceMode::Grapheme`].
+    /// With a layout it removes the last key press.
+    ///
+    /// Returns `None` when there is nothing to remove, in which case the
+    /// frontend should let the backspace through to the application.
+    pub fn backspace(&mut self) -> Option<CompositionUpdate> {
+        if let Some(layout) = self.layout.as_deref_mut() {
+            return layout.backspace();
+        }
+        let mut roman = core::mem::take(&mut self.composition.roman);
+        roman.pop()?;
+
+        if self.config.backspace == BackspaceMode::Grapheme {
+            // Keep removing Roman characters until the last grapheme cluster
+            // of the preedit is gone
+            let graphemes = self.preedit().graphemes(true).count();
+            let (rules, config) = (&self.rules, &self.config);
+            while !roman.is_empty()
+                && rules.convert(config, &roman).graphemes(true).count() >= graphemes
+            {
+                roman.pop();
+            }
+        }
+
+        // A removed character can change how earlier ones matched (`kh` back
+        // to `k`), so the word is converted again from its Roman input.
+        self.reset();
+        let mut context = ProcessingContext::default();
+        let mut output = String::new();
+        for ch in roman.chars() {
+            self.rules.feed(&self.config, &mut context, ch, &mut output);
+        }
+        self.context = context;
+        self.composition = Composition { roman, output };
+
+        Some(self.composition_update(String::new()))
+    }
+
+    /// Finalizes the current composition and hands it over for insertion
+    pub fn commit(&mut self) -> CompositionUpdate {
+        if let Some(layout) = self.layout.as_deref_mut() {
+            return layout.commit();
+        }
+        let mut output = core::mem::take(&mut self.composition.output);
+        self.rules
+            .finish(&self.config, &mut self.context, &mut output);
+        if let Some(text) = self
+            .rules
+            .autocorrected(&self.config, &self.composition.roman)
+        {
+            output = text;
+        }
+        let output = normalized(&self.config, output);
+        let roman = core::mem::take(&mut self.composition.roman);
+        self.reset();
+        self.remember_commit(roman, &output);
+
+        self.composition_update(output)
+    }
+
+    /// Commits `text` in place of the current composition, usually one of
+    /// its candidates
+    pub fn select_candidate(&mut self, text: &str) -> CompositionUpdate {
+        let roman = core::mem::take(&mut self.composition.roman);
+        self.reset();
+        self.remember_commit(roman, text);
+
+        self.composition_update(text.to_string())
+    }
+
+    /// Returns the word committed by the last keystroke, until another
+    /// keystroke arrives
+    pub fn last_commit(&self) -> Option<&CommittedWord> {
+        self.last_commit.as_ref()
+    }
+
+    /// Gives back the last committed word so that its Roman input can
+    /// replace it.
+    ///
+    /// Frontends call this for a backspace that [`Session::backspace`] lets
+    /// through right after a commit: they delete the Bengali text before the
+    /// cursor and insert the Roman text in its place.
+    pub fn revert_last_commit(&mut self) -> Option<CommittedWord> {
+        self.last_commit.take()
+    }
+
+    /// Discards the current composition without committing it
+    pub fn reset(&mut self) {
+        if let Some(layout) = self.layout.as_deref_mut() {
+            layout.reset();
+        }
+        self.context = ProcessingContext::default();
+        self.composition = Composition::default();
+        self.last_commit = None;
+    }
+
+    fn remember_commit(&mut self, roman: String, bengali: &str) {
+        if !roman.is_empty() && !bengali.is_empty() {
+            self.last_commit = Some(CommittedWord {
+                roman,
+                bengali: bengali.to_string(),
+            });
+        }
+    }
+
+    /// Returns the preedit that the current composition would display.
+    ///
+    /// The pending consonant is finished on a copy of the word's state, so
+    /// each call allocates in proportion to the word being typed, not to the
+    /// text before it. [`Session::push_char`] and [`Session::backspace`] call
+    /// this once per keystroke for their [`CompositionUpdate`].
+    pub fn preedit(&self) -> String {
+        if let Some(layout) = self.layout.as_deref() {
+            return layout.preedit();
+        }
+        if let Some(text) = self
+            .rules
+            .autocorrected(&self.config, &self.composition.roman)
+        {
+            return normalized(&self.config, text);
+        }
+        let mut context = self.context.clone();
+        let mut preedit = self.composition.output.clone();
+        self.rules.finish(&self.config, &mut context, &mut preedit);
+        normalized(&self.config, preedit)
+    }
+
+    fn composition_update(&self, commit: String) -> CompositionUpdate {
+        let preedit = self.preedit();
+        let cursor = preedit.chars().count();
+        CompositionUpdate {
+            preedit,
+            commit,
+            cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::Scheme;
+    use std::mem::size_of;
+    use std::thread;
+
+    fn avro() -> Arc<RuleSet> {
+        Arc::new(RuleSet::new(&Scheme::avro()))
+    }
+
+    #[test]
+    fn test_rules_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<RuleSet>();
+        assert_send_sync::<Session>();
+    }
+
+    #[test]
+    fn test_sessions_share_rules() {
+        let rules = avro();
+        let sessions: Vec<Session> = (0..100).map(|_| Session::new(Arc::clone(&rules))).collect();
+        assert_eq!(Arc::strong_count(&rules), 101);
+        assert!(sessions
+            .iter()
+            .all(|session| Arc::ptr_eq(session.rules(), &rules)));
+    }
+
+    #[test]
+    fn test_layout_mode() {
+        let mut session = Session::new(avro());
+        session.set_layout(Some(Layout::bijoy()));
+        assert_eq!(
+            session.layout().map(|layout| layout.name.as_str()),
+            Some("bijoy")
+        );
+
+        session.push_char('c');
+        let update = session.push_key(KeyPress::from_us_char('j'));
+        assert_eq!(update.preedit, "কে");
+        assert_eq!(session.backspace().unwrap().preedit, "ে");
+        session.push_char('h');
+        assert_eq!(session.push_char(' ').commit, "বে ");
+        assert_eq!(session.process_input("cj"), "কে");
+
+        session.set_layout(None);
+        session.push_key(KeyPress::new('k'));
+        assert_eq!(session.preedit(), "ক");
+        assert_eq!(session.process_input("ami"), "আমি");
+    }
+
+    #[test]
+    fn test_session_memory() {
+        // The per-session cost stays small whatever the size of the scheme
+        assert!(size_of::<Session>() <= 512, "{}", size_of::<Session>());
+
+        let mut session = Session::new(avro());
+        for ch in "bangla".chars() {
+            session.push_char(ch);
+        }
+        session.commit();
+        assert_eq!(session.composition.roman.capacity(), 0);
+        assert_eq!(session.composition.output.capacity(), 0);
+        assert_eq!(session.context.pending_input.capacity(), 0);
+    }
+
+    #[test]
+    fn test_sessions_are_independent() {
+        let rules = avro();
+        let mut first = Session::new(Arc::clone(&rules));
+        let mut second = Session::new(rules);
+
+        first.push_char('k');
+        second.push_char('g');
+        first.push_char('h');
+        assert_eq!(first.preedit(), "খ");
+        assert_eq!(second.preedit(), "গ");
+        assert_eq!(second.commit().commit, "গ");
+        assert_eq!(first.preedit(), "খ");
+    }
+
+    #[test]
+    fn test_sessions_across_threads() {
+        let rules = avro();
+        let handles: Vec<_> = ["ami", "tumi", "se"]
+            .into_iter()
+            .map(|word| {
+                let rules = Arc::clone(&rules);
+                thread::spawn(move || {
+                    let mut session = Session::new(rules);
+                    for ch in word.chars() {
+                        session.push_char(ch);
+                    }
+                    session.commit().commit
+                })
+            })
+            .collect();
+
+        let words: Vec<String> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(words, ["আমি", "তুমি", "সে"]);
+    }
+}
@@ -0,0 +1,360 @@
+// crates/core/engine/src/stream/mod.rs
+
+//! Conversion of input too large to hold in memory
+//!
+//! [`convert_stream`] reads Roman text chunk by chunk and writes the Bengali
+//! text as soon as it is final. Between chunks it keeps only the state of the
+//! word being converted, so memory use does not grow with the input. The
+//! output is the same as [`RuleSet::process_input`] gives for the whole text.
+
+use crate::error::{Error, Result};
+use crate::processor::{is_punctuation, normalized, RuleSet};
+use crate::types::{ProcessingContext, ProcessorConfig};
+use std::io::{BufRead, Write};
+
+/// Longest word, in bytes, checked against autocorrect entries; the rules
+/// convert longer words on their own
+pub const MAX_WORD_LEN: usize = 256;
+
+/// Characters held back at the end of the output while normalization may
+/// still change them
+const MAX_CLUSTER_LEN: usize = 8;
+
+/// Converts Roman text from `reader` and writes the Bengali text to
+/// `writer`.
+///
+/// The input must be UTF-8; a character split between two chunks is joined
+/// before it is converted.
+pub fn convert_stream(
+    rules: &RuleSet,
+    config: &ProcessorConfig,
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+) -> Result<()> {
+    let mut stream = Stream::new(rules, config);
+    // Leading bytes of a character that the next chunk completes
+    let mut partial: Vec<u8> = Vec::with_capacity(4);
+    // Bytes of input before the current chunk
+    let mut position = 0;
+
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        let len = chunk.len();
+
+        let mut start = 0;
+        while !partial.is_empty() && start < len {
+            partial.push(chunk[start]);
+            start += 1;
+            match std::str::from_utf8(&partial) {
+                Ok(text) => {
+                    stream.push_str(text);
+                    partial.clear();
+                }
+                Err(error) if error.error_len().is_some() => {
+                    return Err(invalid_utf8(position + start - partial.len()));
+                }
+                Err(_) => {}
+            }
+        }
+
+        let text = match std::str::from_utf8(&chunk[start..]) {
+            Ok(text) => text,
+            Err(error) => {
+                let end = start + error.valid_up_to();
+                if error.error_len().is_some() {
+                    return Err(invalid_utf8(position + end));
+                }
+                partial.extend_from_slice(&chunk[end..]);
+                // Everything before `end` is valid
+                std::str::from_utf8(&chunk[start..end]).unwrap_or_default()
+            }
+        };
+        stream.push_str(text);
+
+        position += len;
+        reader.consume(len);
+        stream.write_to(&mut writer, false)?;
+    }
+
+    if !partial.is_empty() {
+        return Err(invalid_utf8(position - partial.len()));
+    }
+    stream.finish();
+    stream.write_to(&mut writer, true)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn invalid_utf8(offset: usize) -> Error {
+    Error::InvalidInput(format!("invalid UTF-8 at byte {}", offset))
+}
+
+/// Conversion state carried from one chunk to the next
+struct Stream<'a> {
+    rules: &'a RuleSet,
+    config: &'a ProcessorConfig,
+    context: ProcessingContext,
+    /// Converted text that normalization has not seen yet
+    converted: String,
+    /// Text ready to be written
+    ready: String,
+    /// Roman word read so far, while it may still have an autocorrect entry
+    word: String,
+    /// Whether the word outgrew [`MAX_WORD_LEN`] and goes to the rules
+    /// directly until it ends
+    long_word: bool,
+    /// Whether the input is inside a `{...}` block
+    verbatim: bool,
+    /// Whether the last character was a backslash, which turns a following
+    /// `{` into a literal brace
+    escape: bool,
+}
+
+impl<'a> Stream<'a> {
+    fn new(rules: &'a RuleSet, config: &'a ProcessorConfig) -> Self {
+        Self {
+            rules,
+            config,
+            context: ProcessingContext::default(),
+            converted: String::new(),
+            ready: String::new(),
+            word: String::new(),
+            long_word: false,
+            verbatim: false,
+            escape: false,
+        }
+    }
+
+    fn push_str(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.push(ch);
+        }
+    }
+
+    fn push(&mut self, ch: char) {
+        if self.verbatim {
+            match ch {
+                '}' => self.verbatim = false,
+                _ => self.ready.push(ch),
+            }
+            return;
+        }
+
+        if std::mem::take(&mut self.escape) {
+            if ch == '{' {
+                self.push_roman('{');
+                return;
+            }
+            self.push_roman('\\');
+            // `\\` is a backslash of its own and escapes nothing
+            if ch == '\\' {
+                self.push_roman('\\');
+                return;
+            }
+        }
+        match ch {
+            '\\' => self.escape = true,
+            '{' => {
+                self.end_segment();
+                self.verbatim = true;
+            }
+            _ => self.push_roman(ch),
+        }
+    }
+
+    fn push_roman(&mut self, ch: char) {
+        if self.rules.autocorrect().is_empty() || self.long_word {
+            self.rules
+                .feed(self.config, &mut self.context, ch, &mut self.converted);
+            self.long_word &= !is_punctuation(ch);
+            return;
+        }
+
+        self.word.push(ch);
+        if is_punctuation(ch) {
+            self.end_word();
+        } else if self.word.len() > MAX_WORD_LEN {
+            for ch in std::mem::take(&mut self.word).chars() {
+                self.rules
+                    .feed(self.config, &mut self.context, ch, &mut self.converted);
+            }
+            self.long_word = true;
+        }
+    }
+
+    fn end_word(&mut self) {
+        if !self.word.is_empty() {
+            let word = std::mem::take(&mut self.word);
+            self.rules
+                .push_word(self.config, &mut self.context, &word, &mut self.converted);
+        }
+    }
+
+    /// Finishes the Roman text before a `{...}` block or the end of input
+    fn end_segment(&mut self) {
+        self.end_word();
+        self.rules
+            .finish(self.config, &mut self.context, &mut self.converted);
+        self.context = ProcessingContext::default();
+        self.long_word = false;
+        let converted = std::mem::take(&mut self.converted);
+        self.ready.push_str(&normalized(self.config, converted));
+    }
+
+    fn finish(&mut self) {
+        if std::mem::take(&mut self.escape) {
+            self.push_roman('\\');
+        }
+        if !self.verbatim {
+            self.end_segment();
+        }
+    }
+
+    /// Writes the text that is final; at the end of input (`at_end`) that is
+    /// all of it
+    fn write_to(&mut self, writer: &mut impl Write, at_end: bool) -> Result<()> {
+        let settled = if at_end || self.config.normalization.is_none() {
+            self.converted.len()
+        } else {
+            unsettled_start(&self.converted)
+        };
+        if settled > 0 {
+            let converted: String = self.converted.drain(..settled).collect();
+            self.ready.push_str(&normalized(self.config, converted));
+        }
+
+        writer.write_all(self.ready.as_bytes())?;
+        self.ready.clear();
+        Ok(())
+    }
+}
+
+/// Returns where the last cluster of `text` starts, whose normal form can
+/// change when marks follow it
+fn unsettled_start(text: &str) -> usize {
+    text.char_indices()
+        .rev()
+        .take(MAX_CLUSTER_LEN)
+        .find(|&(_, ch)| !is_mark(ch))
+        .map_or(text.len(), |(i, _)| i)
+}
+
+fn is_mark(ch: char) -> bool {
+    matches!(ch, '\u{0981}'..='\u{0983}' | '\u{09BC}'..='\u{09D7}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autocorrect::Autocorrect;
+    use crate::scheme::Scheme;
+    use obadh_bengali::normalize::Normalizer;
+    use std::io::BufReader;
+
+    fn convert_in_chunks(
+        rules: &RuleSet,
+        config: &ProcessorConfig,
+        input: &str,
+        size: usize,
+    ) -> String {
+        let reader = BufReader::with_capacity(size, input.as_bytes());
+        let mut output = Vec::new();
+        convert_stream(rules, config, reader, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_matches_process_input() {
+        let mut autocorrect = Autocorrect::new();
+        autocorrect.insert("adda", "আড্ডা");
+        let rules = RuleSet::new(&Scheme::avro()).with_autocorrect(autocorrect);
+        let configs = [
+            ProcessorConfig::default(),
+            ProcessorConfig {
+                normalization: Some(Normalizer::nfc()),
+                ..ProcessorConfig::default()
+            },
+        ];
+        let input =
+            "amar sonar bangla, adda dei {Rust} \\{x} \\\\{y} \\\\\\{z} kSN ray\nbondhu amar akash";
+
+        for config in &configs {
+            let expected = rules.process_input(config, input);
+            for size in 1..=16 {
+                assert_eq!(
+                    convert_in_chunks(&rules, config, input, size),
+                    expected,
+                    "{}",
+                    size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_characters() {
+        // Bengali and other multi-byte input is copied through, whichever
+        // chunk its bytes arrive in
+        let rules = RuleSet::new(&Scheme::avro());
+        let config = ProcessorConfig::default();
+        for size in 1..=4 {
+            assert_eq!(
+                convert_in_chunks(&rules, &config, "ami আমি — ok", size),
+                "আমি আমি — অক"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let rules = RuleSet::new(&Scheme::avro());
+        let config = ProcessorConfig::default();
+        for input in [&b"ami \xff"[..], &b"ami \xe0\xa6"[..]] {
+            let result = convert_stream(&rules, &config, input, Vec::new());
+            assert!(matches!(result, Err(Error::InvalidInput(message)) if message.ends_with(" 4")));
+        }
+    }
+
+    #[test]
+    fn test_bounded_memory() {
+        let mut autocorrect = Autocorrect::new();
+        autocorrect.insert("adda", "আড্ডা");
+        let rules = RuleSet::new(&Scheme::avro()).with_autocorrect(autocorrect);
+        let config = ProcessorConfig {
+            normalization: Some(Normalizer::default()),
+            ..ProcessorConfig::default()
+        };
+        let mut stream = Stream::new(&rules, &config);
+
+        // A long text, and words that never end, one of them repeating a
+        // phala
+        let text = "amar sonar bangla ".repeat(1000);
+        let words = format!("{}{}", "k".repeat(10_000), "w".repeat(10_000));
+        let mut written = 0;
+        for chunk in text
+            .as_bytes()
+            .chunks(64)
+            .chain(words.as_bytes().chunks(64))
+        {
+            stream.push_str(std::str::from_utf8(chunk).unwrap());
+            let mut output = Vec::new();
+            stream.write_to(&mut output, false).unwrap();
+            written += output.len();
+
+            assert!(stream.word.len() <= MAX_WORD_LEN);
+            assert!(stream.converted.len() <= 64 * 4);
+            assert!(stream.context.pending_input.len() <= 64);
+            let cluster = stream.context.pending_consonant.as_deref();
+            assert!(cluster.unwrap_or_default().len() <= 64);
+        }
+        assert!(written > text.len());
+
+        let mut rest = Vec::new();
+        stream.finish();
+        stream.write_to(&mut rest, true).unwrap();
+        assert!(!rest.is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+// crates/core/engine/src/trace/mod.rs
+
+//! Step-by-step account of a conversion, for bug reports
+//!
+//! [`RuleSet::trace`] converts like [`RuleSet::process_input`] and records a
+//! [`TraceStep`] for each span of input: the pattern that matched, the
+//! characters it stood for and the output it wrote. A trace serializes to
+//! JSON; this is the step for `t` in `kt`:
+//!
+//! ```json
+//! {
+//!   "kind": "mapping",
+//!   "span": { "start": 1, "end": 2 },
+//!   "pattern": "t",
+//!   "chars": [{ "Consonant": "ত" }],
+//!   "hasanta": true,
+//!   "conjunct_blocked": false,
+//!   "output": { "start": 0, "end": 6 }
+//! }
+//! ```
+
+use crate::error::Result;
+use crate::processor::{input_offset, is_punctuation, split_verbatim, RuleSet, Segment};
+use crate::scheme::ConjunctFallback;
+use crate::types::{BengaliChar, ProcessingContext, ProcessorConfig};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+use serde::{Deserialize, Serialize};
+
+/// What handled a span of input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepKind {
+    /// A pattern from the scheme tables
+    Mapping,
+    /// A contextual rule of a pattern
+    Rule,
+    /// A pattern attached as a phala to the consonant before
+    Phala,
+    /// A pattern that starts a reph
+    Reph,
+    /// `o` after a consonant, which keeps the consonant from joining the next
+    Blocker,
+    /// A character no pattern matched, copied or converted by the config
+    Unmapped,
+    /// A word replaced by its autocorrect entry
+    Autocorrect,
+    /// A `{...}` block copied as it is
+    Verbatim,
+    /// The consonant still pending where the Roman text ends
+    Flush,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub kind: StepKind,
+    /// Byte range of the input handled
+    pub span: Range<usize>,
+    /// Scheme pattern that matched, in the case it is written in the scheme
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Characters the pattern stood for, after choosing between alternatives
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chars: Vec<BengaliChar>,
+    /// Whether a hasanta joined the consonant before to this one
+    pub hasanta: bool,
+    /// Whether a preceding `o` kept the consonant before from joining
+    pub conjunct_blocked: bool,
+    /// What the conjunct policy did instead of joining the consonant before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conjunct_fallback: Option<ConjunctFallback>,
+    /// Byte range of the output written.
+    ///
+    /// A consonant is written once the next input shows whether it joins a
+    /// conjunct, so the output of a step can start with that of earlier ones.
+    pub output: Range<usize>,
+}
+
+impl TraceStep {
+    pub(crate) fn new(kind: StepKind, span: Range<usize>) -> Self {
+        Self {
+            kind,
+            span,
+            pattern: None,
+            chars: Vec::new(),
+            hasanta: false,
+            conjunct_blocked: false,
+            conjunct_fallback: None,
+            output: 0..0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trace {
+    pub input: String,
+    /// Output before normalization, which the steps refer to
+    pub output: String,
+    pub steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+pub(crate) fn trace(rules: &RuleSet, config: &ProcessorConfig, input: &str) -> Trace {
+    let mut output = String::new();
+    let mut steps = Vec::new();
+
+    for (start, segment) in split_verbatim(input) {
+        match segment {
+            Segment::Roman(text) => {
+                let mut context = ProcessingContext {
+                    trace: Some(Vec::new()),
+                    ..ProcessingContext::default()
+                };
+                for word in text.split_inclusive(is_punctuation) {
+                    rules.push_word(config, &mut context, word, &mut output);
+                }
+                rules.finish(config, &mut context, &mut output);
+
+                for mut step in context.trace.unwrap_or_default() {
+                    step.span = input_offset(&text, start, step.span.start)
+                        ..input_offset(&text, start, step.span.end);
+                    steps.push(step);
+                }
+            }
+            Segment::Verbatim(text) => {
+                // The span includes the braces
+                let end = (start + text.len() + 1).min(input.len());
+                let mut step = TraceStep::new(StepKind::Verbatim, start - 1..end);
+                step.output = output.len()..output.len() + text.len();
+                output.push_str(text);
+                steps.push(step);
+            }
+        }
+    }
+
+    Trace {
+        input: input.to_string(),
+        output,
+        steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autocorrect::Autocorrect;
+    use crate::scheme::{ConjunctPolicy, Scheme};
+
+    fn avro_trace(input: &str) -> Trace {
+        RuleSet::new(&Scheme::avro()).trace(&ProcessorConfig::default(), input)
+    }
+
+    #[test]
+    fn test_steps_cover_input_and_output() {
+        let mut autocorrect = Autocorrect::new();
+        autocorrect.insert("adda", "আড্ডা");
+        let rules = RuleSet::new(&Scheme::avro()).with_autocorrect(autocorrect);
+        let config = ProcessorConfig::default();
+        let input = "amar bondhu, adda {Rust} \\{x} kSN";
+        let trace = rules.trace(&config, input);
+
+        assert_eq!(trace.output, rules.process_input(&config, input));
+        let mut input_end = 0;
+        let mut output_end = 0;
+        for step in &trace.steps {
+            assert!(step.span.start >= input_end, "{:?}", step);
+            assert_eq!(step.output.start, output_end, "{:?}", step);
+            input_end = step.span.end;
+            output_end = step.output.end;
+        }
+        assert_eq!(output_end, trace.output.len());
+    }
+
+    #[test]
+    fn test_conjunct_steps() {
+        let trace = avro_trace("kt");
+        assert_eq!(trace.steps.len(), 3);
+        assert_eq!(trace.steps[0].pattern.as_deref(), Some("k"));
+        assert_eq!(trace.steps[0].chars, [BengaliChar::Consonant('ক')]);
+        assert_eq!(trace.steps[0].output, 0..0);
+        assert!(trace.steps[1].hasanta);
+        assert_eq!(&trace.output[trace.steps[1].output.clone()], "ক্");
+        assert_eq!(trace.steps[2].kind, StepKind::Flush);
+        assert_eq!(&trace.output[trace.steps[2].output.clone()], "ত");
+
+        let trace = avro_trace("kot");
+        assert_eq!(trace.steps[1].kind, StepKind::Blocker);
+        assert!(trace.steps[2].conjunct_blocked);
+        assert!(!trace.steps[2].hasanta);
+    }
+
+    #[test]
+    fn test_pattern_and_rule_steps() {
+        let trace = avro_trace("Kh");
+        assert_eq!(trace.steps[0].span, 0..2);
+        assert_eq!(trace.steps[0].pattern.as_deref(), Some("kh"));
+
+        let trace = avro_trace("ky");
+        assert_eq!(trace.steps[1].kind, StepKind::Phala);
+
+        let trace = avro_trace("kkh");
+        assert_eq!(trace.steps[0].kind, StepKind::Rule);
+        assert_eq!(
+            trace.steps[0].chars,
+            [BengaliChar::Consonant('ক'), BengaliChar::Consonant('ষ')]
+        );
+
+        let mut scheme = Scheme::avro();
+        scheme.conjuncts = Some(ConjunctPolicy::default());
+        let trace = RuleSet::new(&scheme).trace(&ProcessorConfig::default(), "kg");
+        assert_eq!(
+            trace.steps[1].conjunct_fallback,
+            Some(ConjunctFallback::Inherent)
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let trace = avro_trace("ka {x}");
+        let json = trace.to_json().unwrap();
+        assert!(json.contains(r#""kind": "verbatim""#), "{}", json);
+        assert!(json.contains(r#""pattern": "k""#), "{}", json);
+        assert_eq!(serde_json::from_str::<Trace>(&json).unwrap(), trace);
+    }
+}
@@ -0,0 +1,185 @@
+// crates/core/engine/src/trie/mod.rs
+
+//! Prefix trie for longest-match pattern lookup
+//!
+//! The trie is walked one character at a time through [`Cursor`]s, so the
+//! processor can follow several paths at once (e.g. exact and case-folded)
+//! without building intermediate strings.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Position of a walk through a [`PatternTrie`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cursor(usize);
+
+#[derive(Clone, Debug)]
+struct TrieNode<T> {
+    /// Outgoing edges, sorted by character
+    children: Vec<(char, usize)>,
+    value: Option<T>,
+}
+
+impl<T> TrieNode<T> {
+    fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            value: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PatternTrie<T> {
+    nodes: Vec<TrieNode<T>>,
+}
+
+impl<T> PatternTrie<T> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![TrieNode::new()],
+        }
+    }
+
+    /// Inserts a pattern, replacing any value it already had
+    pub fn insert(&mut self, pattern: &str, value: T) {
+        let mut node = 0;
+        for ch in pattern.chars() {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&ch, |&(c, _)| c)
+            {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::new());
+                    self.nodes[node].children.insert(i, (ch, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].value = Some(value);
+    }
+
+    /// Looks up the value of an exact pattern
+    pub fn get(&self, pattern: &str) -> Option<&T> {
+        let mut cursor = self.root();
+        for ch in pattern.chars() {
+            cursor = self.advance(cursor, ch)?;
+        }
+        self.value(cursor)
+    }
+
+    /// Finds the longest pattern that is a prefix of `input`.
+    ///
+    /// Returns the length of the match in bytes together with its value.
+    pub fn longest_match(&self, input: &str) -> Option<(usize, &T)> {
+        let mut cursor = self.root();
+        let mut best = None;
+
+        for (i, ch) in input.char_indices() {
+            match self.advance(cursor, ch) {
+                Some(next) => cursor = next,
+                None => break,
+            }
+            if let Some(value) = self.value(cursor) {
+                best = Some((i + ch.len_utf8(), value));
+            }
+        }
+
+        best
+    }
+
+    pub fn root(&self) -> Cursor {
+        Cursor(0)
+    }
+
+    /// Follows the edge for `ch`, if there is one
+    pub fn advance(&self, cursor: Cursor, ch: char) -> Option<Cursor> {
+        let children = &self.nodes[cursor.0].children;
+        children
+            .binary_search_by_key(&ch, |&(c, _)| c)
+            .ok()
+            .map(|i| Cursor(children[i].1))
+    }
+
+    /// Returns the value of the pattern ending at `cursor`
+    pub fn value(&self, cursor: Cursor) -> Option<&T> {
+        self.nodes[cursor.0].value.as_ref()
+    }
+
+    /// Checks whether a longer pattern continues from `cursor`
+    pub fn has_continuation(&self, cursor: Cursor) -> bool {
+        !self.nodes[cursor.0].children.is_empty()
+    }
+}
+
+impl<T> Default for PatternTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(String, T)> for PatternTrie<T> {
+    fn from_iter<I: IntoIterator<Item = (String, T)>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for (pattern, value) in iter {
+            trie.insert(&pattern, value);
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PatternTrie<u32> {
+        let mut trie = PatternTrie::new();
+        trie.insert("k", 1);
+        trie.insert("kh", 2);
+        trie.insert("rri", 3);
+        trie.insert("abcdefgh", 4);
+        trie
+    }
+
+    #[test]
+    fn test_get() {
+        let trie = sample();
+        assert_eq!(trie.get("kh"), Some(&2));
+        assert_eq!(trie.get("rr"), None);
+        assert_eq!(trie.get(""), None);
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let trie = sample();
+        assert_eq!(trie.longest_match("kha"), Some((2, &2)));
+        assert_eq!(trie.longest_match("ka"), Some((1, &1)));
+        assert_eq!(trie.longest_match("rra"), None);
+        assert_eq!(trie.longest_match("abcdefghij"), Some((8, &4)));
+    }
+
+    #[test]
+    fn test_cursor_walk() {
+        let trie = sample();
+        let r = trie.advance(trie.root(), 'r').unwrap();
+        let rr = trie.advance(r, 'r').unwrap();
+        assert!(trie.value(rr).is_none());
+        assert!(trie.has_continuation(rr));
+
+        let rri = trie.advance(rr, 'i').unwrap();
+        assert_eq!(trie.value(rri), Some(&3));
+        assert!(!trie.has_continuation(rri));
+        assert!(trie.advance(rri, 'i').is_none());
+    }
+
+    #[test]
+    fn test_insert_replaces_value() {
+        let mut trie = sample();
+        trie.insert("kh", 5);
+        assert_eq!(trie.get("kh"), Some(&5));
+        assert_eq!(trie.get("k"), Some(&1));
+    }
+}
@@ -2,7 +2,14 @@
 
 //! Types used in the Bengali input engine
 
-#[derive(Clone, Debug)]
+use crate::trace::{StepKind, TraceStep};
+use alloc::string::String;
+use alloc::vec::Vec;
+use obadh_bengali::normalize::Normalizer;
+use obadh_bengali::utils::{KHANDA_TA, ZWJ, ZWNJ};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BengaliChar {
     Vowel(char),
     Consonant(char),
@@ -10,11 +17,166 @@ pub enum BengaliChar {
     Special(char),
     Symbol(char),
     Compound(Vec<char>),
+    /// Ends a consonant with a visible hasanta instead of joining the next one
+    Zwnj,
+    /// Ends a consonant with a hasanta that asks for its half or ligature form
+    Zwj,
+    /// ৎ, which ends the consonant cluster before it
+    KhandaTa,
+}
+
+impl BengaliChar {
+    /// Returns the character this stands for, unless it is a compound
+    pub fn to_char(&self) -> Option<char> {
+        match self {
+            BengaliChar::Vowel(ch)
+            | BengaliChar::Consonant(ch)
+            | BengaliChar::VowelSign(ch)
+            | BengaliChar::Special(ch)
+            | BengaliChar::Symbol(ch) => Some(*ch),
+            BengaliChar::Compound(_) => None,
+            BengaliChar::Zwnj => Some(ZWNJ),
+            BengaliChar::Zwj => Some(ZWJ),
+            BengaliChar::KhandaTa => Some(KHANDA_TA),
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct ProcessingContext {
     pub previous: Option<BengaliChar>,
     pub previous_output: Option<String>,
     pub prevent_conjunct: bool,
+    /// Consonant waiting to learn whether it joins a conjunct or takes a vowel sign
+    pub pending_consonant: Option<String>,
+    /// Roman input that is still a prefix of a longer mapping
+    pub pending_input: String,
+    /// Tail of the Roman input already converted, used by prefix rules
+    pub previous_input: String,
+    /// Byte offset of the pending input in the text being converted
+    pub offset: usize,
+    /// Steps of the conversion, recorded when this is `Some`
+    pub trace: Option<Vec<TraceStep>>,
+}
+
+impl ProcessingContext {
+    /// Starts a trace step for the next `len` bytes of pending input, with
+    /// the output so far `output` bytes long
+    pub(crate) fn begin_step(
+        &mut self,
+        kind: StepKind,
+        len: usize,
+        output: usize,
+        pattern: Option<String>,
+    ) {
+        if let Some(trace) = self.trace.as_mut() {
+            let mut step = TraceStep::new(kind, self.offset..self.offset + len);
+            step.pattern = pattern;
+            step.output = output..output;
+            trace.push(step);
+        }
+    }
+
+    /// Returns the trace step being recorded, if any
+    pub(crate) fn step(&mut self) -> Option<&mut TraceStep> {
+        self.trace.as_mut()?.last_mut()
+    }
+
+    /// Ends the trace step with the output now `output` bytes long
+    pub(crate) fn end_step(&mut self, output: usize) {
+        if let Some(step) = self.step() {
+            step.output.end = output;
+        }
+    }
+}
+
+/// Conversion of digits and ASCII punctuation that no scheme pattern covers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProcessorConfig {
+    /// Write 0-9 as ০-৯
+    pub bengali_digits: bool,
+    /// Write `.` as the dari `।`
+    pub dari: bool,
+    /// Write `$` as the taka sign `৳`
+    pub taka: bool,
+    /// Normalize converted text, e.g. to choose the form of ড়, ঢ় and য়
+    pub normalization: Option<Normalizer>,
+    /// What one backspace removes from the word being composed
+    pub backspace: BackspaceMode,
+}
+
+impl Default for ProcessorConfig {
+    fn default() -> Self {
+        Self {
+            bengali_digits: false,
+            dari: false,
+            taka: false,
+            normalization: None,
+            backspace: BackspaceMode::Roman,
+        }
+    }
+}
+
+impl ProcessorConfig {
+    /// Returns the Bengali form of an unmapped character under this config
+    pub fn convert(&self, ch: char) -> char {
+        match ch {
+            '0'..='9' if self.bengali_digits => {
+                char::from_u32('০' as u32 + (ch as u32 - '0' as u32)).unwrap_or(ch)
+            }
+            '.' if self.dari => '।',
+            '$' if self.taka => '৳',
+            _ => ch,
+        }
+    }
+}
+
+/// What one backspace removes from the word being composed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackspaceMode {
+    /// The last Roman character; the rest of the word is converted again
+    #[default]
+    Roman,
+    /// The last Bengali grapheme cluster, with the Roman characters that
+    /// typed it
+    Grapheme,
+}
+
+/// Result of a single keystroke in incremental mode
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompositionUpdate {
+    /// Bengali text of the word being composed; it may still change
+    pub preedit: String,
+    /// Bengali text that is final and should be inserted by the frontend
+    pub commit: String,
+    /// Cursor position within the preedit, in characters
+    pub cursor: usize,
+}
+
+/// Where a suggested Bengali word came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateSource {
+    /// A word the user picked for this Roman input before
+    History,
+    /// A user autocorrect entry for the whole word
+    Autocorrect,
+    /// A word list entry that the Roman input can spell
+    Dictionary,
+    /// The rule-based conversion of the Roman input
+    Rules,
+}
+
+/// The Roman input and Bengali output of the last committed word
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommittedWord {
+    pub roman: String,
+    pub bengali: String,
+}
+
+/// A Bengali word offered for the Roman word being typed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub text: String,
+    pub source: CandidateSource,
 }
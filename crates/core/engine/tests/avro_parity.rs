@@ -0,0 +1,107 @@
+// crates/core/engine/tests/avro_parity.rs
+
+//! Reference Avro Phonetic input/output pairs.
+//!
+//! Every pair is checked through the batch API and keystroke by keystroke,
+//! so both paths stay on the published Avro behaviour. The expected Bengali
+//! is also reversed to Roman and converted back. The table below pins the
+//! cases by hand; the pairs in `corpus/avro.json` go through the same checks.
+
+use obadh_engine::{Corpus, Processor, Reverser, Scheme};
+use pretty_assertions::assert_eq;
+use test_case::test_case;
+
+fn check(input: &str, expected: &str) {
+    let mut processor = Processor::new();
+    assert_eq!(processor.process_input(input), expected, "batch: {}", input);
+
+    let mut typed = String::new();
+    for ch in input.chars() {
+        typed.push_str(&processor.push_char(ch).commit);
+    }
+    typed.push_str(&processor.commit().commit);
+    assert_eq!(typed, expected, "incremental: {}", input);
+
+    let roman = Reverser::new(&Scheme::avro()).reverse(expected);
+    assert_eq!(
+        processor.process_input(&roman),
+        expected,
+        "reverse: {}",
+        roman
+    );
+}
+
+// Vowels, vowel signs and the inherent vowel
+#[test_case("ami", "আমি")]
+#[test_case("amar", "আমার")]
+#[test_case("onek", "অনেক")]
+#[test_case("tumi", "তুমি")]
+#[test_case("sOnar", "সোনার")]
+#[test_case("bhalobasa", "ভালবাসা")]
+#[test_case("porIkkha", "পরীক্ষা")]
+#[test_case("swadhInota", "স্বাধীনতা")]
+#[test_case("rritu", "ঋতু")]
+#[test_case("krriShok", "কৃষক")]
+#[test_case("jhOR", "ঝোড়")]
+#[test_case("neel", "নীল")]
+#[test_case("dooR", "দুড়")]
+#[test_case("kOUshol", "কৌশল")]
+#[test_case("OIkko", "ঐক্ক")]
+// Vowels after vowels
+#[test_case("khaoa", "খাওয়া")]
+#[test_case("noa", "নয়া")]
+#[test_case("ia", "ইয়া")]
+#[test_case("bao", "বাও")]
+#[test_case("aa", "আআ")]
+// Consonants and conjuncts
+#[test_case("sundor", "সুন্দর")]
+#[test_case("shokti", "শক্তি")]
+#[test_case("kothay", "কথায়")]
+#[test_case("bhaSha", "ভাষা")]
+#[test_case("Rhak", "ঢ়াক")]
+#[test_case("kkhoma", "ক্ষমা")]
+#[test_case("bikkhobh", "বিক্ষভ")]
+#[test_case("xor", "এক্সর")]
+#[test_case("bax", "বাক্স")]
+#[test_case("qolom", "কলম")]
+// Phala and reph
+#[test_case("gram", "গ্রাম")]
+#[test_case("kormo", "কর্ম")]
+#[test_case("korrmo", "কর্ম")]
+#[test_case("bidyut", "বিদ্যুত")]
+#[test_case("priyo", "প্রিয়")]
+#[test_case("kyano", "ক্যান")]
+#[test_case("bakZo", "বাক্য")]
+#[test_case("swami", "স্বামি")]
+#[test_case("ZZa", "্যা")]
+#[test_case("rZab", "র\u{200D}্যাব")]
+#[test_case("podmo", "পদ্ম")]
+#[test_case("smrriti", "স্মৃতি")]
+#[test_case("dwar", "দ্বার")]
+#[test_case("bZakoron", "ব্যাকরন")]
+#[test_case("korrtobZo", "কর্তব্য")]
+#[test_case("koy", "কয়")]
+// Anusvara, visarga, chandrabindu
+#[test_case("bangla", "বাংলা")]
+#[test_case("bangladesh", "বাংলাদেশ")]
+#[test_case("ingreji", "ইংরেজি")]
+#[test_case("du:kh", "দুঃখ")]
+#[test_case("ca^d", "চাঁদ")]
+// Khanda ta
+#[test_case("ut``sob", "উৎসব")]
+#[test_case("hoThat``", "হঠাৎ")]
+// Punctuation
+#[test_case("ki.", "কি.")]
+#[test_case("ami tumi, se.", "আমি তুমি, সে.")]
+fn avro_reference(input: &str, expected: &str) {
+    check(input, expected);
+}
+
+#[test]
+fn avro_corpus() {
+    let corpus = Corpus::avro();
+    assert!(!corpus.cases.is_empty());
+    for case in &corpus.cases {
+        check(&case.input, &case.expected);
+    }
+}
@@ -0,0 +1,31 @@
+// crates/core/engine/tests/conformance.rs
+
+//! Runs the shared conformance corpora against the built-in schemes.
+//!
+//! The files in `corpus/` are the ones other frontends run too, so a change
+//! in behaviour shows up here before it reaches them.
+
+use obadh_engine::{Corpus, Scheme};
+use std::fs;
+use std::path::Path;
+
+fn scheme(name: &str) -> Scheme {
+    match name {
+        "avro" => Scheme::avro(),
+        _ => panic!("no built-in scheme named {}", name),
+    }
+}
+
+#[test]
+fn test_corpora() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    let mut ran = 0;
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let corpus = Corpus::from_file(&path).unwrap();
+        let report = corpus.run(&scheme(&corpus.scheme));
+        assert!(report.passed(), "{}: {}", path.display(), report);
+        ran += report.cases;
+    }
+    assert!(ran > 0);
+}
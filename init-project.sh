@@ -49,9 +49,8 @@ pub mod utils;
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn sanity_check() {
         assert!(true);
     }